@@ -0,0 +1,69 @@
+//! Brand mentions in an answer
+
+use super::text;
+use crate::commands::api::ProductInfo;
+use crate::storage::ProductConfig;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// How the brand may be written in an answer
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrandTerms {
+    pub name: String,
+    /// Other names that count as the brand, e.g. "HubSpot CRM" or "HS"
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Phrases that contain a name but don't mean the brand, e.g. "box office" for "Box"
+    #[serde(default)]
+    pub exclusions: Vec<String>,
+    /// Match names only in the case they are written in (acronyms always are)
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+impl BrandTerms {
+    /// Terms for a brand, without empty or repeated aliases and exclusions
+    pub fn new(
+        name: &str,
+        aliases: impl IntoIterator<Item = String>,
+        exclusions: impl IntoIterator<Item = String>,
+        case_sensitive: bool,
+    ) -> Self {
+        let mut terms = BrandTerms { name: name.to_string(), case_sensitive, ..Default::default() };
+        for alias in aliases {
+            if !alias.trim().is_empty() && !terms.aliases.contains(&alias) && alias != name {
+                terms.aliases.push(alias);
+            }
+        }
+        for exclusion in exclusions {
+            if !exclusion.trim().is_empty() && !terms.exclusions.contains(&exclusion) {
+                terms.exclusions.push(exclusion);
+            }
+        }
+        terms
+    }
+
+    /// The product's terms with the aliases and exclusions set locally added
+    pub fn for_product(product: &ProductInfo, config: &ProductConfig) -> Self {
+        Self::new(
+            &product.brand,
+            product.brand_aliases.iter().chain(&config.brand_aliases).cloned(),
+            product.brand_exclusions.iter().chain(&config.brand_exclusions).cloned(),
+            product.brand_case_sensitive || config.brand_case_sensitive,
+        )
+    }
+
+    /// Byte ranges where the brand is mentioned in `text`, in order
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let names: Vec<&str> = std::iter::once(self.name.as_str())
+            .chain(self.aliases.iter().map(String::as_str))
+            .collect();
+        let exclusions: Vec<&str> = self.exclusions.iter().map(String::as_str).collect();
+
+        text::find_terms(text, &names, &exclusions, self.case_sensitive)
+            .into_iter()
+            .map(|m| m.range)
+            .collect()
+    }
+}
@@ -0,0 +1,200 @@
+//! Competitor mentions in an answer
+
+use super::text;
+use crate::{CompetitorDetailResult, TextSpan};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A competitor and the names it goes by
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", from = "CompetitorSpec")]
+pub struct Competitor {
+    pub name: String,
+    /// Other names for the company, e.g. "MS" for "Microsoft"
+    pub aliases: Vec<String>,
+    /// Product names that count as a mention, e.g. "Teams"
+    pub products: Vec<String>,
+    /// Domains that count as a mention when written out, e.g. "monday.com"
+    pub domains: Vec<String>,
+    /// A mention is ignored if one of these occurs in the same sentence, e.g. "Tuesday"
+    /// or "morning" for "Monday"
+    pub negative_keywords: Vec<String>,
+}
+
+/// Competitors are plain names in older API responses and checkpoints
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CompetitorSpec {
+    Name(String),
+    #[serde(rename_all = "camelCase")]
+    Full {
+        name: String,
+        #[serde(default)]
+        aliases: Vec<String>,
+        #[serde(default)]
+        products: Vec<String>,
+        #[serde(default)]
+        domains: Vec<String>,
+        #[serde(default)]
+        negative_keywords: Vec<String>,
+    },
+}
+
+impl From<CompetitorSpec> for Competitor {
+    fn from(spec: CompetitorSpec) -> Self {
+        match spec {
+            CompetitorSpec::Name(name) => Competitor { name, ..Default::default() },
+            CompetitorSpec::Full { name, aliases, products, domains, negative_keywords } => {
+                Competitor { name, aliases, products, domains, negative_keywords }
+            }
+        }
+    }
+}
+
+impl Competitor {
+    /// Everything that counts as a mention: name, aliases, products and bare domains
+    fn terms(&self) -> Vec<String> {
+        let domains = self.domains.iter().map(|d| {
+            let d = d.trim().trim_start_matches("https://").trim_start_matches("http://");
+            d.trim_start_matches("www.").trim_end_matches('/').to_string()
+        });
+
+        let mut terms: Vec<String> = Vec::new();
+        for term in std::iter::once(self.name.clone())
+            .chain(self.aliases.iter().cloned())
+            .chain(self.products.iter().cloned())
+            .chain(domains)
+        {
+            if !term.trim().is_empty() && !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        terms
+    }
+
+    /// Byte ranges where the competitor is mentioned, with the term that matched
+    fn find(&self, text: &str, sentences: &[Range<usize>]) -> Vec<(Range<usize>, String)> {
+        let terms = self.terms();
+        let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+
+        // Sentences that name a negative keyword don't count
+        let negative_sentences: Vec<&Range<usize>> = sentences.iter()
+            .filter(|s| self.negative_keywords.iter().any(|k| !text::find_term(&text[(*s).clone()], k, false).is_empty()))
+            .collect();
+
+        text::find_terms(text, &term_refs, &[], false)
+            .into_iter()
+            .filter(|m| !negative_sentences.iter().any(|s| s.start <= m.range.start && m.range.start < s.end))
+            .map(|m| (m.range, terms[m.term].clone()))
+            .collect()
+    }
+}
+
+/// Add locally configured aliases, products, domains and negative keywords to the
+/// competitors of the same name
+pub fn with_local_terms(mut competitors: Vec<Competitor>, local: &[Competitor]) -> Vec<Competitor> {
+    for competitor in &mut competitors {
+        let Some(extra) = local.iter().find(|l| l.name.eq_ignore_ascii_case(&competitor.name)) else {
+            continue;
+        };
+        let lists = [
+            (&mut competitor.aliases, &extra.aliases),
+            (&mut competitor.products, &extra.products),
+            (&mut competitor.domains, &extra.domains),
+            (&mut competitor.negative_keywords, &extra.negative_keywords),
+        ];
+        for (list, additions) in lists {
+            for addition in additions {
+                if !list.contains(addition) {
+                    list.push(addition.clone());
+                }
+            }
+        }
+    }
+    competitors
+}
+
+/// Competitors mentioned in `text` in the order they are first mentioned, each with the
+/// byte ranges of its mentions. Position and sentiment are left unset.
+pub fn find_competitors(text: &str, competitors: &[Competitor]) -> Vec<(CompetitorDetailResult, Vec<Range<usize>>)> {
+    let sentences = text::sentences(text);
+
+    let mut found: Vec<(CompetitorDetailResult, Vec<Range<usize>>)> = competitors.iter()
+        .filter_map(|competitor| {
+            let matches = competitor.find(text, &sentences);
+            if matches.is_empty() {
+                return None;
+            }
+
+            let mut matched_aliases: Vec<String> = Vec::new();
+            for (_, term) in &matches {
+                if !matched_aliases.contains(term) {
+                    matched_aliases.push(term.clone());
+                }
+            }
+            let ranges: Vec<Range<usize>> = matches.into_iter().map(|(range, _)| range).collect();
+            let spans: Vec<TextSpan> = text::to_spans(text, &ranges);
+
+            Some((CompetitorDetailResult {
+                matched_aliases,
+                mentions: spans.len(),
+                spans,
+                ..CompetitorDetailResult::unrated(&competitor.name)
+            }, ranges))
+        })
+        .collect();
+
+    found.sort_by_key(|(_, ranges)| ranges[0].start);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monday() -> Competitor {
+        Competitor {
+            name: "Monday".to_string(),
+            aliases: vec!["monday.com".to_string()],
+            products: vec!["Monday CRM".to_string()],
+            domains: vec!["https://www.monday.com/".to_string()],
+            negative_keywords: vec!["Tuesday".to_string(), "morning".to_string()],
+        }
+    }
+
+    #[test]
+    fn matches_name_aliases_products_and_domains() {
+        let text = "Monday CRM is popular. See monday.com for pricing. Monday works too.";
+        let found = find_competitors(text, &[monday()]);
+        assert_eq!(found.len(), 1);
+        let (detail, _) = &found[0];
+        assert_eq!(detail.mentions, 3);
+        assert_eq!(detail.matched_aliases, vec!["Monday CRM", "monday.com", "Monday"]);
+    }
+
+    #[test]
+    fn negative_keywords_drop_mentions_in_their_sentence() {
+        let text = "Meet on Monday morning. Plan until Tuesday or Monday.\nMonday is a solid tool.";
+        let found = find_competitors(text, &[monday()]);
+        assert_eq!(found[0].0.mentions, 1);
+        assert_eq!(found[0].1[0].start, text.rfind("Monday").unwrap());
+    }
+
+    #[test]
+    fn ordered_by_first_mention() {
+        let asana = Competitor { name: "Asana".to_string(), ..Default::default() };
+        let text = "Asana and Monday are both fine.";
+        let names: Vec<String> = find_competitors(text, &[monday(), asana])
+            .into_iter()
+            .map(|(d, _)| d.name)
+            .collect();
+        assert_eq!(names, vec!["Asana", "Monday"]);
+    }
+
+    #[test]
+    fn plain_names_deserialize() {
+        let competitors: Vec<Competitor> = serde_json::from_str(r#"["Asana", {"name": "Monday", "domains": ["monday.com"]}]"#).unwrap();
+        assert_eq!(competitors[0], Competitor { name: "Asana".to_string(), ..Default::default() });
+        assert_eq!(competitors[1].domains, vec!["monday.com"]);
+    }
+}
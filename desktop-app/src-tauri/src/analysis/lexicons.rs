@@ -0,0 +1,193 @@
+//! Sentiment lexicons for the languages of our main markets
+//!
+//! Words are lowercase. An entry ending in `*` matches every word that starts with it,
+//! which covers inflected forms ("empfehl*" for "empfehlenswert", "Empfehlung"). The words
+//! are picked for answers that compare products, so "expensive" and "steep" are negative
+//! and "popular" is positive.
+
+pub struct Lexicon {
+    /// Frequent words that tell the language of an answer apart
+    pub stopwords: &'static [&'static str],
+    pub positive: &'static [&'static str],
+    pub negative: &'static [&'static str],
+    /// Flip a sentiment word shortly after them ("not reliable")
+    pub negations: &'static [&'static str],
+    /// Strengthen the sentiment word right after them ("very reliable")
+    pub intensifiers: &'static [&'static str],
+    /// Start a clause with a sentiment of its own ("good, but expensive")
+    pub contrasts: &'static [&'static str],
+}
+
+pub const LEXICONS: &[Lexicon] = &[
+    // English
+    Lexicon {
+        stopwords: &["the", "and", "is", "are", "of", "to", "for", "with", "that", "this", "it", "you", "on", "in", "as"],
+        positive: &[
+            "good", "great", "best", "better", "excellent", "outstanding", "exceptional", "superior",
+            "recommend", "recommended", "recommends", "reliable", "trusted", "easy", "simple",
+            "intuitive", "friendly", "powerful", "popular", "affordable", "effective", "efficient",
+            "flexible", "versatile", "ideal", "robust", "solid", "stable", "fast", "secure",
+            "comprehensive", "leading", "leader", "innovative", "perfect", "strong", "favorite",
+            "favourite", "impressive", "seamless", "seamlessly", "smooth", "love", "loved", "praised",
+            "standout", "top", "valuable", "helpful", "convenient", "polished", "excels", "shines",
+            "advantage", "advantages", "strength", "strengths", "scalable", "generous", "accurate",
+            "responsive",
+        ],
+        negative: &[
+            "bad", "poor", "worse", "worst", "expensive", "overpriced", "costly", "pricey",
+            "complicated", "cumbersome", "clunky", "difficult", "slow", "buggy", "unreliable",
+            "outdated", "limited", "limitation", "limitations", "lack", "lacks", "lacking", "issue",
+            "issues", "problem", "problems", "drawback", "drawbacks", "downside", "downsides", "weak",
+            "weakness", "weaknesses", "confusing", "frustrating", "disappointing", "avoid", "fails",
+            "failed", "failure", "crashes", "criticized", "criticised", "criticism", "complaints",
+            "steep", "insecure", "breach", "breaches", "inferior", "unstable", "restrictive",
+            "mediocre", "lag", "lags", "glitches",
+        ],
+        negations: &["not", "no", "never", "none", "nothing", "neither", "nor", "without", "hardly", "barely", "cannot"],
+        intensifiers: &[
+            "very", "really", "extremely", "highly", "incredibly", "particularly", "exceptionally",
+            "especially", "super", "truly", "remarkably", "most", "so",
+        ],
+        contrasts: &["but", "however", "although", "though", "whereas", "while", "yet"],
+    },
+    // German
+    Lexicon {
+        stopwords: &["der", "die", "das", "und", "ist", "nicht", "mit", "für", "ein", "eine", "auch", "sich", "auf", "den", "von"],
+        positive: &[
+            "gut", "gute*", "besser*", "beste*", "empfehl*", "zuverlässig*", "einfach*", "günstig*",
+            "preiswert*", "leistungsstark*", "hervorragend*", "ausgezeichnet*", "intuitiv*",
+            "benutzerfreundlich*", "beliebt*", "führend*", "stark", "starke*", "sichere*", "flexib*",
+            "schnell*", "vielseitig*", "ideal*", "überzeugend*", "praktisch*", "effizient*",
+            "innovativ*", "umfassend*", "solide*", "top", "toll*", "perfekt*", "vertrauenswürdig*",
+            "vorteil*", "stärke*",
+        ],
+        negative: &[
+            "schlecht*", "teuer", "teure*", "überteuert*", "kompliziert*", "umständlich*",
+            "langsam*", "fehlerhaft*", "unzuverlässig*", "veraltet*", "schwach*", "schwäche*",
+            "eingeschränkt*", "begrenzt*", "mangel*", "nachteil*", "problem", "probleme",
+            "problemen", "schwierig*", "unübersichtlich*", "enttäusch*", "kritisiert*", "kritik*",
+            "vermeid*", "unsicher*", "störend*", "unausgereift*", "fehlt", "fehlen*", "abstürz*",
+        ],
+        negations: &["nicht", "kein", "keine", "keinen", "keinem", "keiner", "keines", "nie", "niemals", "ohne", "kaum", "weder", "nichts"],
+        intensifiers: &["sehr", "besonders", "äußerst", "extrem", "wirklich", "höchst", "absolut", "ausgesprochen", "echt", "ziemlich", "zu"],
+        contrasts: &["aber", "jedoch", "allerdings", "obwohl", "dennoch", "sondern", "während"],
+    },
+    // French
+    Lexicon {
+        stopwords: &["le", "la", "les", "et", "est", "des", "une", "un", "pour", "avec", "dans", "que", "qui", "sur", "du"],
+        positive: &[
+            "bon", "bons", "bonne*", "meilleur*", "excellent*", "recommand*", "fiable*", "facile*",
+            "intuiti*", "puissant*", "populaire*", "abordable*", "efficace*", "flexible*",
+            "polyvalent*", "idéal*", "robuste*", "rapide*", "sécurisé*", "complet", "complète*",
+            "apprécié*", "performant*", "innovant*", "remarquable*", "parfait*", "pratique*",
+            "leader*", "solide*", "avantage*", "atout*",
+        ],
+        negative: &[
+            "mauvais*", "mauvaise*", "cher", "chère*", "chers", "coûteu*", "compliqué*", "lent",
+            "lente*", "lents", "limité*", "difficile*", "problème*", "inconvénient*", "défaut*",
+            "faible*", "obsolète*", "décevant*", "critiqué*", "éviter", "bogu*", "manque*",
+            "lourd*", "frustrant*", "risqu*", "pire*", "instable*",
+        ],
+        negations: &["ne", "pas", "jamais", "aucun", "aucune", "sans", "ni", "rien", "guère"],
+        intensifiers: &["très", "vraiment", "extrêmement", "particulièrement", "hautement", "super", "trop", "totalement"],
+        contrasts: &["mais", "cependant", "toutefois", "pourtant", "néanmoins", "tandis"],
+    },
+    // Spanish
+    Lexicon {
+        stopwords: &["el", "la", "los", "las", "y", "es", "que", "en", "un", "una", "para", "con", "por", "del", "se"],
+        positive: &[
+            "bueno*", "buena*", "buen", "mejor*", "excelente*", "recomend*", "fiable*", "confiable*",
+            "fácil*", "intuitiv*", "potente*", "popular*", "asequible*", "económic*", "eficaz",
+            "eficaces", "eficiente*", "flexible*", "versátil*", "ideal*", "robust*", "rápid*",
+            "segur*", "complet*", "líder*", "destacad*", "innovador*", "perfect*", "práctic*",
+            "sólid*", "ventaja*",
+        ],
+        negative: &[
+            "mal", "malo*", "mala*", "caro", "caros", "costos*", "complicad*", "lent*", "limitad*",
+            "difícil*", "problema*", "inconveniente*", "desventaja*", "débil*", "obsolet*",
+            "decepcion*", "criticad*", "evitar", "peor*", "defectuos*", "frustrante*", "carece*",
+            "carencia*", "falla*", "fallo*", "inestable*",
+        ],
+        negations: &["no", "nunca", "jamás", "ningún", "ninguna", "ninguno", "sin", "ni", "nada", "tampoco"],
+        intensifiers: &["muy", "realmente", "extremadamente", "especialmente", "particularmente", "sumamente", "altamente", "súper", "bastante", "demasiado"],
+        contrasts: &["pero", "aunque", "embargo", "mientras"],
+    },
+    // Italian
+    Lexicon {
+        stopwords: &["il", "di", "che", "è", "per", "con", "una", "non", "sono", "del", "della", "gli", "lo", "anche", "più"],
+        positive: &[
+            "buon", "buono*", "buona*", "miglior*", "ottim*", "eccellent*", "consigli*",
+            "affidabil*", "facil*", "intuitiv*", "potent*", "popolar*", "convenient*", "economic*",
+            "efficac*", "efficient*", "flessibil*", "versatil*", "ideal*", "robust*", "veloc*",
+            "rapid*", "sicur*", "complet*", "leader", "innovativ*", "perfett*", "pratic*", "solid*",
+            "vantagg*",
+        ],
+        negative: &[
+            "cattiv*", "scars*", "costos*", "caro", "cari", "complicat*", "lent*", "limitat*",
+            "difficil*", "problem*", "svantagg*", "difett*", "debol*", "obsolet*", "deludent*",
+            "criticat*", "evitare", "peggior*", "frustrant*", "mancan*", "instabil*",
+        ],
+        negations: &["non", "mai", "nessun", "nessuno", "nessuna", "senza", "né", "niente", "nulla"],
+        intensifiers: &["molto", "davvero", "estremamente", "particolarmente", "altamente", "super", "assolutamente", "veramente", "decisamente", "troppo"],
+        contrasts: &["ma", "però", "tuttavia", "sebbene", "mentre", "nonostante"],
+    },
+    // Dutch
+    Lexicon {
+        stopwords: &["de", "het", "een", "en", "is", "van", "voor", "met", "niet", "dat", "die", "op", "zijn", "ook", "maar"],
+        positive: &[
+            "goed", "goede*", "beter*", "beste*", "aanbevol*", "aanrader*", "aanraden",
+            "betrouwba*", "eenvoudig*", "makkelijk*", "gebruiksvriendelijk*", "intuïtie*",
+            "krachtig*", "populair*", "betaalba*", "voordelig*", "efficiënt*", "flexibe*",
+            "veelzijdig*", "ideaal", "ideale*", "robuust*", "snel", "snelle*", "veilig*",
+            "uitgebreid*", "uitstekend*", "toonaangevend*", "innovatie*", "perfect*", "handig*",
+            "sterk*", "solide*", "voordeel", "voordelen",
+        ],
+        negative: &[
+            "slecht*", "duur", "dure*", "ingewikkeld*", "omslachtig*", "traag*", "trage*",
+            "beperkt*", "beperking*", "moeilijk*", "probleem", "problemen", "nadeel*", "nadelen",
+            "zwak*", "verouderd*", "teleurstell*", "kritiek*", "vermijd*", "onbetrouwba*",
+            "onveilig*", "gebrek*", "ontbreekt", "instabie*",
+        ],
+        negations: &["niet", "geen", "nooit", "zonder", "noch", "niets", "nauwelijks"],
+        intensifiers: &["zeer", "erg", "heel", "echt", "bijzonder", "uiterst", "extreem", "enorm", "super", "te"],
+        contrasts: &["maar", "echter", "hoewel", "terwijl", "toch"],
+    },
+    // Portuguese
+    Lexicon {
+        stopwords: &["o", "os", "é", "que", "em", "um", "uma", "para", "com", "não", "do", "da", "dos", "das", "mais"],
+        positive: &[
+            "bom", "boa", "boas", "bons", "melhor*", "excelent*", "recomend*", "confiáve*", "fácil",
+            "fáceis", "intuitiv*", "potent*", "poderos*", "popular*", "acessíve*", "económic*",
+            "econômic*", "eficaz*", "eficient*", "flexíve*", "versáti*", "ideal*", "robust*",
+            "rápid*", "segur*", "complet*", "líder*", "destacad*", "inovador*", "perfeit*",
+            "prátic*", "sólid*", "vantage*",
+        ],
+        negative: &[
+            "mau", "má", "ruim", "ruins", "caro", "caros", "dispendios*", "complicad*", "lent*",
+            "limitad*", "difícil", "difíceis", "problema*", "desvantage*", "defeito*", "fraco*",
+            "fraca*", "obsolet*", "decepcion*", "criticad*", "evitar", "pior*", "frustrante*",
+            "falha*", "carece*", "instáve*",
+        ],
+        negations: &["não", "nunca", "jamais", "nenhum", "nenhuma", "sem", "nem", "nada"],
+        intensifiers: &["muito", "realmente", "extremamente", "especialmente", "particularmente", "altamente", "super", "bastante", "demasiado"],
+        contrasts: &["mas", "porém", "contudo", "embora", "entretanto", "enquanto"],
+    },
+    // Polish
+    Lexicon {
+        stopwords: &["i", "w", "na", "jest", "się", "nie", "z", "do", "to", "że", "dla", "od", "jak", "oraz", "są"],
+        positive: &[
+            "dobr*", "lepsz*", "najlepsz*", "polec*", "niezawodn*", "łatw*", "intuicyjn*",
+            "wydajn*", "popularn*", "przystępn*", "tani", "tania", "tanie", "elastyczn*",
+            "wszechstronn*", "idealn*", "solidn*", "szybk*", "bezpieczn*", "kompleksow*",
+            "doskonał*", "świetn*", "wiodąc*", "innowacyjn*", "praktyczn*", "zalet*",
+        ],
+        negative: &[
+            "zły", "zła", "złe", "słab*", "kosztown*", "skomplikowan*", "powoln*", "ograniczon*",
+            "trudn*", "problem*", "wad*", "przestarzał*", "rozczarow*", "krytykowan*", "unikać",
+            "gorsz*", "najgorsz*", "brak*", "niestabiln*",
+        ],
+        negations: &["nie", "nigdy", "bez", "żaden", "żadna", "żadne", "ani", "nic"],
+        intensifiers: &["bardzo", "naprawdę", "wyjątkowo", "szczególnie", "niezwykle", "super", "niezmiernie", "zbyt"],
+        contrasts: &["ale", "jednak", "chociaż", "choć", "natomiast", "lecz"],
+    },
+];
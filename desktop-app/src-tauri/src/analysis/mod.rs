@@ -0,0 +1,82 @@
+//! Local analysis of collected answers
+//!
+//! Runs in Rust on the response text the collect script read from the page, so it gives
+//! the same result at collection time and when a stored answer is re-analyzed.
+//!
+//! - `brand`: where the brand is mentioned, by name or alias, on whole words
+//! - `competitors`: which competitors are mentioned, by name, alias, product or domain,
+//!   and how often
+//! - `position`: where the brand and each competitor rank in the answer's lists, tables
+//!   and headings
+//! - `sentiment`: how positively the answer speaks of each, from multilingual lexicons
+//!   (`lexicons`) and a few rules, without network or an LLM
+
+pub mod brand;
+pub mod competitors;
+mod lexicons;
+mod position;
+pub mod sentiment;
+mod text;
+
+pub use brand::BrandTerms;
+pub use competitors::Competitor;
+
+use crate::{CompetitorDetailResult, TextSpan};
+
+/// What the local analysis found in one answer
+#[derive(Clone, Debug, Default)]
+pub struct Analysis {
+    pub brand_spans: Vec<TextSpan>,
+    /// Rank of the brand, None if it isn't mentioned
+    pub position: Option<i32>,
+    /// "positive", "negative" or "neutral"
+    pub sentiment: String,
+    /// From -1 to 1, None if the brand isn't mentioned
+    pub sentiment_score: Option<f64>,
+    /// Mentioned competitors in the order they are first mentioned, with their ranks and
+    /// sentiment
+    pub competitor_details: Vec<CompetitorDetailResult>,
+}
+
+impl Analysis {
+    pub fn brand_mentioned(&self) -> bool {
+        !self.brand_spans.is_empty()
+    }
+
+    pub fn competitor_mentions(&self) -> Vec<String> {
+        self.competitor_details.iter().map(|cd| cd.name.clone()).collect()
+    }
+}
+
+/// Find the brand and competitors in `text`, rank them and rate how they come across
+pub fn analyze(text: &str, brand: &BrandTerms, competitors: &[Competitor]) -> Analysis {
+    let brand_ranges = brand.find(text);
+    let found = competitors::find_competitors(text, competitors);
+
+    let mentions: Vec<_> = std::iter::once(brand_ranges.clone())
+        .chain(found.iter().map(|(_, ranges)| ranges.clone()))
+        .collect();
+    let ranks = position::rank(text, &mentions);
+
+    let lexicon = sentiment::detect_language(text);
+    let sentences = text::sentences(text);
+    let scores: Vec<Option<f64>> = mentions.iter()
+        .map(|ranges| sentiment::score(text, &sentences, lexicon, ranges))
+        .collect();
+
+    Analysis {
+        brand_spans: text::to_spans(text, &brand_ranges),
+        position: ranks[0],
+        sentiment: sentiment::label(scores[0]).to_string(),
+        sentiment_score: scores[0],
+        competitor_details: found.into_iter()
+            .zip(ranks.into_iter().zip(scores).skip(1))
+            .map(|((detail, _), (rank, score))| CompetitorDetailResult {
+                position: rank,
+                sentiment: sentiment::label(score).to_string(),
+                sentiment_score: score,
+                ..detail
+            })
+            .collect(),
+    }
+}
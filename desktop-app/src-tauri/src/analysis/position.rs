@@ -0,0 +1,331 @@
+//! Ranks from the structure of an answer
+//!
+//! Lines are read as the items of ranked lists:
+//!
+//! - numbered items ("1.", "2)", "#3") take the number they are written with
+//! - bullet items ("-", "*", "•") take their place among the bullets next to them
+//! - table rows take their place below the header row; rows are Markdown ("| a | b |") or
+//!   tab-separated, which is how rendered tables come out of `innerText`
+//! - headings ("## Notion", or a short line standing on its own between blank lines) take
+//!   their place among the headings of the same level, and cover the text below them
+//! - short lines right below each other without markers are a list too, since rendered
+//!   lists lose their markers in `innerText`: two or more after a line introducing them
+//!   ("Here are the best tools:"), or three or more that don't end like sentences. Other
+//!   runs of short lines are prose and aren't ranked.
+//!
+//! A name's rank is that of the innermost item around its first mention in any item.
+//! Names that no item mentions are ranked after those that are, by where they are first
+//! mentioned.
+
+use std::ops::Range;
+
+/// Lines longer than this aren't list items without a marker, nor headings
+const MAX_IMPLICIT_ITEM_LEN: usize = 200;
+const MAX_HEADING_LEN: usize = 80;
+/// Unmarked lines without an introducing line it takes to make a list
+const MIN_UNINTRODUCED_ITEMS: usize = 3;
+
+fn ends_like_sentence(line: &str) -> bool {
+    line.trim_end().ends_with(['.', '!', '?', ',', ';', ':', '。', '！', '？'])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LineKind {
+    Blank,
+    Numbered(usize),
+    Bullet,
+    /// Markdown heading level, or 0 for a line standing on its own
+    Heading(usize),
+    TableRow,
+    TableSeparator,
+    Text,
+}
+
+struct Line {
+    range: Range<usize>,
+    indent: usize,
+    kind: LineKind,
+}
+
+/// An item of a ranked list and the text it covers
+struct Item {
+    range: Range<usize>,
+    rank: usize,
+}
+
+fn classify(line: &str) -> LineKind {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return LineKind::Blank;
+    }
+
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(char::is_whitespace) {
+        return LineKind::Heading(hashes);
+    }
+
+    if let Some(number) = list_number(trimmed) {
+        return LineKind::Numbered(number);
+    }
+
+    let mut chars = trimmed.chars();
+    if let (Some(marker), Some(next)) = (chars.next(), chars.next()) {
+        if "-*+•◦▪‣–·".contains(marker) && next.is_whitespace() {
+            return LineKind::Bullet;
+        }
+    }
+
+    if trimmed.contains('-') && trimmed.chars().all(|c| "|-: \t".contains(c)) {
+        return LineKind::TableSeparator;
+    }
+    if trimmed.starts_with('|') || line.trim_end_matches(['\r', '\n']).contains('\t') {
+        return LineKind::TableRow;
+    }
+
+    LineKind::Text
+}
+
+/// The number of a numbered list item: "1.", "2)", "3:" or "#4" followed by whitespace
+fn list_number(trimmed: &str) -> Option<usize> {
+    let rest = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 || digits > 3 {
+        return None;
+    }
+
+    let after = &rest[digits..];
+    let marked = trimmed.starts_with('#') || after.starts_with(['.', ')', ':']);
+    let after = after.strip_prefix(['.', ')', ':']).unwrap_or(after);
+    if marked && after.starts_with(char::is_whitespace) {
+        rest[..digits].parse().ok()
+    } else {
+        None
+    }
+}
+
+fn split_lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        lines.push(Line {
+            range: start..start + content.len(),
+            indent: content.chars().take_while(|c| c.is_whitespace()).count(),
+            kind: classify(line),
+        });
+        start += line.len();
+    }
+
+    // A short line between blank lines that doesn't end like a sentence reads as a heading
+    for i in 0..lines.len() {
+        let standalone = (i == 0 || lines[i - 1].kind == LineKind::Blank)
+            && lines.get(i + 1).is_some_and(|l| l.kind == LineKind::Blank);
+        let content = text[lines[i].range.clone()].trim();
+        if lines[i].kind == LineKind::Text
+            && standalone
+            && content.len() <= MAX_HEADING_LEN
+            && !ends_like_sentence(content)
+        {
+            lines[i].kind = LineKind::Heading(0);
+        }
+    }
+    lines
+}
+
+/// Index of the first line after `from` that isn't part of the item starting at `from`:
+/// the item's line plus the lines indented deeper, and bullets below a numbered item
+fn item_end(lines: &[Line], from: usize) -> usize {
+    let head = &lines[from];
+    let mut end = from + 1;
+    while let Some(line) = lines.get(end) {
+        let nested = line.indent > head.indent
+            || (matches!(head.kind, LineKind::Numbered(_)) && line.kind == LineKind::Bullet);
+        if line.kind == LineKind::Blank || !nested {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+fn ranked_items(text: &str) -> Vec<Item> {
+    let lines = split_lines(text);
+    let span = |from: usize, to: usize| lines[from].range.start..lines[to - 1].range.end;
+    let mut items = Vec::new();
+
+    // Headings cover the text up to the next heading of the same or a higher level
+    let mut heading_counts: Vec<usize> = vec![0; 7];
+    for (i, line) in lines.iter().enumerate() {
+        if let LineKind::Heading(level) = line.kind {
+            heading_counts[level] += 1;
+            heading_counts[level + 1..].iter_mut().for_each(|c| *c = 0);
+            let end = lines[i + 1..].iter()
+                .position(|l| matches!(l.kind, LineKind::Heading(other) if other <= level || level == 0))
+                .map(|p| i + 1 + p)
+                .unwrap_or(lines.len());
+            items.push(Item { range: span(i, end), rank: heading_counts[level] });
+        }
+    }
+
+    let mut i = 0;
+    while i < lines.len() {
+        match lines[i].kind {
+            LineKind::Numbered(number) => {
+                let end = item_end(&lines, i);
+                items.push(Item { range: span(i, end), rank: number });
+                i = end;
+            }
+            LineKind::Bullet => {
+                let indent = lines[i].indent;
+                let mut rank = 0;
+                while lines.get(i).is_some_and(|l| l.kind == LineKind::Bullet && l.indent == indent) {
+                    let end = item_end(&lines, i);
+                    rank += 1;
+                    items.push(Item { range: span(i, end), rank });
+                    i = end;
+                }
+            }
+            LineKind::TableRow => {
+                // The first row is the header
+                let mut rank = 0;
+                i += 1;
+                while let Some(line) = lines.get(i) {
+                    match line.kind {
+                        LineKind::TableSeparator => {}
+                        LineKind::TableRow => {
+                            rank += 1;
+                            items.push(Item { range: line.range.clone(), rank });
+                        }
+                        _ => break,
+                    }
+                    i += 1;
+                }
+            }
+            LineKind::Text => {
+                // A line introducing the list ("Here are the best tools:") isn't an item
+                let run = lines[i..].iter()
+                    .take_while(|l| {
+                        l.kind == LineKind::Text
+                            && l.range.len() <= MAX_IMPLICIT_ITEM_LEN
+                            && !text[l.range.clone()].trim_end().ends_with(':')
+                    })
+                    .count();
+                let introduced = lines[..i].iter()
+                    .rev()
+                    .find(|l| l.kind != LineKind::Blank)
+                    .is_some_and(|l| l.kind == LineKind::Text && text[l.range.clone()].trim_end().ends_with(':'));
+                let list_like = run >= MIN_UNINTRODUCED_ITEMS
+                    && lines[i..i + run].iter().all(|l| !ends_like_sentence(&text[l.range.clone()]));
+                if run >= 2 && (introduced || list_like) {
+                    for (rank, line) in lines[i..i + run].iter().enumerate() {
+                        items.push(Item { range: line.range.clone(), rank: rank + 1 });
+                    }
+                }
+                i += run.max(1);
+            }
+            _ => i += 1,
+        }
+    }
+
+    items
+}
+
+/// Rank of each name, given the byte ranges where it is mentioned in `text` (in text
+/// order). Names without mentions get None.
+pub fn rank(text: &str, mentions: &[Vec<Range<usize>>]) -> Vec<Option<i32>> {
+    let items = ranked_items(text);
+
+    // The innermost item around a mention, e.g. the list item rather than the heading above it
+    let innermost = |r: &Range<usize>| {
+        items.iter()
+            .filter(|item| item.range.start <= r.start && r.end <= item.range.end)
+            .min_by_key(|item| item.range.len())
+            .map(|item| item.rank)
+    };
+    let mut ranks: Vec<Option<usize>> = mentions.iter()
+        .map(|ranges| ranges.iter().find_map(innermost))
+        .collect();
+
+    // Mentioned outside any list: after the listed names, by first mention
+    let listed_max = ranks.iter().flatten().copied().max().unwrap_or(0);
+    let mut unlisted: Vec<(usize, usize)> = mentions.iter()
+        .enumerate()
+        .filter(|(i, _)| ranks[*i].is_none())
+        .filter_map(|(i, ranges)| ranges.first().map(|r| (r.start, i)))
+        .collect();
+    unlisted.sort();
+    for (order, (_, i)) in unlisted.into_iter().enumerate() {
+        ranks[i] = Some(listed_max + order + 1);
+    }
+
+    ranks.into_iter().map(|r| r.map(|r| r as i32)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ranks of `names`, each found by plain substring search
+    fn ranks(text: &str, names: &[&str]) -> Vec<Option<i32>> {
+        let mentions: Vec<Vec<Range<usize>>> = names.iter()
+            .map(|name| text.match_indices(name).map(|(i, m)| i..i + m.len()).collect())
+            .collect();
+        rank(text, &mentions)
+    }
+
+    #[test]
+    fn numbered_lists_keep_their_numbers() {
+        let text = "Top picks:\n\n1. Asana - simple\n2) Notion - flexible\n#3 ClickUp";
+        assert_eq!(ranks(text, &["Asana", "Notion", "ClickUp"]), vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn bullets_rank_in_order_with_nested_details() {
+        let text = "- Asana\n  - great for teams using Notion\n- Notion\n* ClickUp";
+        // Notion's first mention is nested under Asana, so it takes that bullet's rank
+        assert_eq!(ranks(text, &["Asana", "Notion", "ClickUp"]), vec![Some(1), Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn table_rows_rank_below_the_header() {
+        let text = "| Tool | Price |\n|---|---|\n| Notion | $8 |\n| Asana | $10 |";
+        assert_eq!(ranks(text, &["Asana", "Notion", "Tool"]), vec![Some(2), Some(1), Some(3)]);
+        let text = "Tool\tPrice\nNotion\t$8\nAsana\t$10";
+        assert_eq!(ranks(text, &["Asana", "Notion"]), vec![Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn headings_rank_among_their_level() {
+        let text = "## Asana\nGood for teams.\n\n## Notion\nGood for docs, unlike ClickUp.";
+        assert_eq!(ranks(text, &["Notion", "Asana", "ClickUp"]), vec![Some(2), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn introduced_implicit_lists_are_ranked() {
+        let text = "The best tools are:\nNotion for docs\nAsana for tasks";
+        assert_eq!(ranks(text, &["Asana", "Notion"]), vec![Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn unmarked_label_lines_are_a_list() {
+        let text = "Notion\nAsana\nClickUp";
+        assert_eq!(ranks(text, &["ClickUp", "Notion"]), vec![Some(3), Some(1)]);
+    }
+
+    #[test]
+    fn short_prose_lines_are_not_a_list() {
+        // Two short sentences one after the other are a paragraph, not a second ranking:
+        // they rank after the list by first mention
+        let text = "1. ClickUp\n\nAsana is a fine choice.\nNotion is popular too.";
+        assert_eq!(ranks(text, &["ClickUp", "Asana", "Notion"]), vec![Some(1), Some(2), Some(3)]);
+        // Two unmarked lines without an introduction aren't enough either
+        let text = "1. ClickUp\n\nAsana\nNotion";
+        assert_eq!(ranks(text, &["ClickUp", "Asana", "Notion"]), vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn unlisted_names_rank_after_listed_ones() {
+        let text = "Also consider Trello.\n\n1. Notion\n2. Asana";
+        assert_eq!(ranks(text, &["Trello", "Asana", "Jira"]), vec![Some(3), Some(2), None]);
+    }
+}
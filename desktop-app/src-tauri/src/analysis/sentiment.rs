@@ -0,0 +1,268 @@
+//! Sentiment towards a name from the sentences around its mentions
+//!
+//! The answer's language is that of the lexicon whose stopwords occur most often in it
+//! (English if none do). A mention is scored on its clause, the part of its sentence
+//! between contrast words, so "Notion is great, but Asana is slow" rates each name on its
+//! own half. If the clause carries no sentiment the whole sentence counts, and if the
+//! sentence is little more than the name (a heading or list item title), the next one.
+//!
+//! Each positive word counts +1 and each negative word -1. An intensifier right before a
+//! word makes it count `INTENSIFIER_WEIGHT` times, and a negation up to `NEGATION_REACH`
+//! words before flips it ("not reliable", "nicht zu teuer"). The sum over the clauses
+//! around all mentions is squashed to -1..1 the way VADER does: x / sqrt(x² + `ALPHA`).
+
+use super::lexicons::{Lexicon, LEXICONS};
+use std::ops::Range;
+
+const INTENSIFIER_WEIGHT: f64 = 1.5;
+/// A negated word counts this much of its weight, in the other direction
+const NEGATION_WEIGHT: f64 = -0.75;
+/// Words after a negation that it applies to
+const NEGATION_REACH: usize = 3;
+/// The larger, the more sentiment words it takes for a score near -1 or 1
+const ALPHA: f64 = 15.0;
+/// Scores closer to 0 than this are neutral
+const NEUTRAL_BAND: f64 = 0.05;
+/// A sentence with at most this many words besides the name introduces the next one
+const TITLE_WORDS: usize = 3;
+
+struct Word {
+    range: Range<usize>,
+    /// Lowercase, with typographic apostrophes made straight
+    text: String,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\'' || c == '\u{2019}'
+}
+
+/// Words in `range` of `text`
+fn words(text: &str, range: Range<usize>) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    let offset = range.start;
+    for (i, c) in text[range.clone()].char_indices().chain(std::iter::once((range.len(), ' '))) {
+        match (start, is_word_char(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                let raw = &text[offset + s..offset + i];
+                let trimmed = raw.trim_matches(['\'', '\u{2019}']);
+                if !trimmed.is_empty() {
+                    let lead = raw.len() - raw.trim_start_matches(['\'', '\u{2019}']).len();
+                    let word_start = offset + s + lead;
+                    words.push(Word {
+                        range: word_start..word_start + trimmed.len(),
+                        text: trimmed.to_lowercase().replace('\u{2019}', "'"),
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// True if `word` is in `list`, where entries ending in `*` match as prefixes
+fn listed(list: &[&str], word: &str) -> bool {
+    list.iter().any(|entry| match entry.strip_suffix('*') {
+        Some(prefix) => word.starts_with(prefix),
+        None => *entry == word,
+    })
+}
+
+/// The word without an elided article or pronoun: "l'excellent" → "excellent"
+fn without_elision(word: &str) -> &str {
+    match word.split_once('\'') {
+        Some((head, tail)) if head.chars().count() <= 2 && !tail.is_empty() => tail,
+        _ => word,
+    }
+}
+
+fn is_negation(lexicon: &Lexicon, word: &str) -> bool {
+    listed(lexicon.negations, word) || word.ends_with("n't") || word.starts_with("n'")
+}
+
+/// Summed sentiment of `words`, skipping those in `skip` (the name itself); None if none
+/// of them carries sentiment
+fn valence(lexicon: &Lexicon, words: &[Word], skip: &[Range<usize>]) -> Option<f64> {
+    let mut sum = None;
+    for (i, word) in words.iter().enumerate() {
+        if skip.iter().any(|s| s.start < word.range.end && word.range.start < s.end) {
+            continue;
+        }
+        let stem = without_elision(&word.text);
+        let mut value = if listed(lexicon.positive, stem) {
+            1.0
+        } else if listed(lexicon.negative, stem) {
+            -1.0
+        } else {
+            continue;
+        };
+
+        if i > 0 && listed(lexicon.intensifiers, &words[i - 1].text) {
+            value *= INTENSIFIER_WEIGHT;
+        }
+        if words[i.saturating_sub(NEGATION_REACH)..i].iter().any(|w| is_negation(lexicon, &w.text)) {
+            value *= NEGATION_WEIGHT;
+        }
+        *sum.get_or_insert(0.0) += value;
+    }
+    sum
+}
+
+/// Lexicon for the language `text` is written in
+pub fn detect_language(text: &str) -> &'static Lexicon {
+    let words = words(text, 0..text.len());
+    LEXICONS.iter()
+        .map(|lexicon| (lexicon, words.iter().filter(|w| lexicon.stopwords.contains(&w.text.as_str())).count()))
+        .filter(|(_, hits)| *hits > 0)
+        // The first of equally likely languages, so English wins ties
+        .fold(None, |best: Option<(&Lexicon, usize)>, (lexicon, hits)| match best {
+            Some((_, most)) if most >= hits => best,
+            _ => Some((lexicon, hits)),
+        })
+        .map(|(lexicon, _)| lexicon)
+        .unwrap_or(&LEXICONS[0])
+}
+
+/// The text a mention in sentence `index` is scored on, and its sentiment
+fn context(
+    text: &str,
+    sentences: &[Range<usize>],
+    index: usize,
+    mention: &Range<usize>,
+    lexicon: &Lexicon,
+    skip: &[Range<usize>],
+) -> (Range<usize>, f64) {
+    let sentence = sentences[index].clone();
+    let sentence_words = words(text, sentence.clone());
+
+    let mut clause = sentence.clone();
+    for word in sentence_words.iter().filter(|w| listed(lexicon.contrasts, &w.text)) {
+        if word.range.start <= mention.start {
+            clause.start = word.range.start;
+        } else {
+            clause.end = word.range.start;
+            break;
+        }
+    }
+    let from = sentence_words.iter().position(|w| w.range.start >= clause.start).unwrap_or(sentence_words.len());
+    let to = sentence_words.iter().position(|w| w.range.start >= clause.end).unwrap_or(sentence_words.len());
+    if let Some(value) = valence(lexicon, &sentence_words[from..to], skip) {
+        return (clause, value);
+    }
+
+    if let Some(value) = valence(lexicon, &sentence_words, skip) {
+        return (sentence, value);
+    }
+
+    let other_words = sentence_words.iter()
+        .filter(|w| !skip.iter().any(|s| s.start < w.range.end && w.range.start < s.end))
+        .count();
+    if let Some(next) = sentences.get(index + 1).filter(|_| other_words <= TITLE_WORDS) {
+        if let Some(value) = valence(lexicon, &words(text, next.clone()), skip) {
+            return (next.clone(), value);
+        }
+    }
+    (sentence, 0.0)
+}
+
+/// Sentiment from -1 to 1 towards a name mentioned at `mentions` (byte ranges in text
+/// order), None without mentions
+pub fn score(text: &str, sentences: &[Range<usize>], lexicon: &Lexicon, mentions: &[Range<usize>]) -> Option<f64> {
+    if mentions.is_empty() {
+        return None;
+    }
+
+    // Each clause counts once, however often the name occurs in it
+    let mut scored: Vec<Range<usize>> = Vec::new();
+    let mut total = 0.0;
+    for mention in mentions {
+        let Some(index) = sentences.iter().position(|s| s.start <= mention.start && mention.start < s.end) else {
+            continue;
+        };
+        let (range, value) = context(text, sentences, index, mention, lexicon, mentions);
+        if !scored.contains(&range) {
+            scored.push(range);
+            total += value;
+        }
+    }
+
+    let score = total / (total * total + ALPHA).sqrt();
+    Some((score * 1000.0).round() / 1000.0)
+}
+
+/// "positive", "negative" or "neutral" for a score
+pub fn label(score: Option<f64>) -> &'static str {
+    match score {
+        Some(s) if s >= NEUTRAL_BAND => "positive",
+        Some(s) if s <= -NEUTRAL_BAND => "negative",
+        _ => "neutral",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::text::sentences;
+
+    fn score_of(text: &str, name: &str) -> Option<f64> {
+        let mentions: Vec<Range<usize>> = text.match_indices(name).map(|(i, m)| i..i + m.len()).collect();
+        score(text, &sentences(text), detect_language(text), &mentions)
+    }
+
+    #[test]
+    fn contrast_words_split_a_sentence_into_clauses() {
+        let text = "Notion is great, but Asana is slow.";
+        assert!(score_of(text, "Notion").unwrap() > 0.0);
+        assert!(score_of(text, "Asana").unwrap() < 0.0);
+    }
+
+    #[test]
+    fn negation_flips_the_words_after_it() {
+        assert!(score_of("Asana is not reliable.", "Asana").unwrap() < 0.0);
+        assert!(score_of("Asana is reliable.", "Asana").unwrap() > 0.0);
+        // Beyond the reach the negation no longer applies
+        assert!(score_of("Not that it matters much here, Asana is reliable.", "Asana").unwrap() > 0.0);
+    }
+
+    #[test]
+    fn intensifiers_strengthen_the_next_word() {
+        let plain = score_of("Asana is reliable.", "Asana").unwrap();
+        let strong = score_of("Asana is very reliable.", "Asana").unwrap();
+        assert!(strong > plain);
+    }
+
+    #[test]
+    fn a_title_line_takes_the_sentiment_of_the_next_sentence() {
+        let text = "Asana\nIt is slow and clunky for large teams.";
+        assert!(score_of(text, "Asana").unwrap() < 0.0);
+        // A longer sentence without sentiment stays neutral
+        let text = "Asana is a tool used by many project teams today.\nIt is slow.";
+        assert_eq!(score_of(text, "Asana"), Some(0.0));
+    }
+
+    #[test]
+    fn detects_the_language_from_stopwords() {
+        let german = "Asana ist nicht teuer und auch für kleine Teams gut.";
+        assert_eq!(detect_language(german).stopwords, LEXICONS[1].stopwords);
+        assert_eq!(detect_language("Asana 2024").stopwords, LEXICONS[0].stopwords);
+        // "nicht teuer" is negated, so both words count in favour
+        assert!(score_of(german, "Asana").unwrap() > 0.0);
+    }
+
+    #[test]
+    fn no_mentions_means_no_score() {
+        assert_eq!(score_of("Notion is great.", "Asana"), None);
+    }
+
+    #[test]
+    fn labels_use_the_neutral_band() {
+        assert_eq!(label(Some(0.5)), "positive");
+        assert_eq!(label(Some(NEUTRAL_BAND)), "positive");
+        assert_eq!(label(Some(0.01)), "neutral");
+        assert_eq!(label(Some(-NEUTRAL_BAND)), "negative");
+        assert_eq!(label(None), "neutral");
+    }
+}
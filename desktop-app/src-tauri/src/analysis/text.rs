@@ -0,0 +1,285 @@
+//! Term matching on whole words
+//!
+//! A term matches where the text around it doesn't continue the word: the characters
+//! just before and after must not be letters, digits or `_` (in any script). Edges of a
+//! term that aren't word characters, like the "+" of "C++", need no boundary. Scripts
+//! written without spaces between words (Chinese, Japanese, Thai, Korean particles)
+//! don't mark word ends, so they count as a boundary too.
+//!
+//! Whitespace in a term matches any run of whitespace, and typographic apostrophes
+//! match straight ones.
+
+use crate::TextSpan;
+use std::ops::Range;
+
+/// A match of one of the terms passed to `find_terms`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TermMatch {
+    /// Byte range in the text
+    pub range: Range<usize>,
+    /// Index of the matching term
+    pub term: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Scripts that don't separate words with spaces
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0E7F     // Thai
+        | 0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF)  // CJK Compatibility Ideographs
+}
+
+/// True if `c` next to a term's edge ends the word there (`None` is the text's start or end)
+fn is_boundary(c: Option<char>) -> bool {
+    c.is_none_or(|c| !is_word_char(c) || is_unspaced_script(c))
+}
+
+/// A term's edge character that needs a word boundary next to it
+fn needs_boundary(edge: char) -> bool {
+    is_word_char(edge) && !is_unspaced_script(edge)
+}
+
+fn normalize(c: char) -> char {
+    match c {
+        '\u{2019}' | '\u{2018}' | '\u{02BC}' => '\'',
+        _ => c,
+    }
+}
+
+fn chars_match(a: char, b: char, case_sensitive: bool) -> bool {
+    let (a, b) = (normalize(a), normalize(b));
+    a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// A short all-caps term like "HS" or "IBM" is an acronym; matched in any case it would
+/// hit ordinary words
+pub fn is_acronym(term: &str) -> bool {
+    let letters: Vec<char> = term.chars().filter(|c| c.is_alphabetic()).collect();
+    (2..=5).contains(&letters.len()) && letters.iter().all(|c| c.is_uppercase())
+}
+
+/// Length in bytes of a match of `term` at byte `start` of `text`, if it matches there
+fn match_at(text: &str, start: usize, term: &[char], case_sensitive: bool) -> Option<usize> {
+    let mut text_chars = text[start..].char_indices().peekable();
+    let mut term_chars = term.iter().peekable();
+
+    while let Some(&t) = term_chars.next() {
+        if t.is_whitespace() {
+            while term_chars.next_if(|c| c.is_whitespace()).is_some() {}
+            text_chars.next_if(|(_, c)| c.is_whitespace())?;
+            while text_chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        } else {
+            let (_, c) = text_chars.next()?;
+            if !chars_match(c, t, case_sensitive) {
+                return None;
+            }
+        }
+    }
+
+    Some(text_chars.peek().map(|(i, _)| *i).unwrap_or(text.len() - start))
+}
+
+/// Byte ranges where `term` occurs in `text` as a whole word
+pub fn find_term(text: &str, term: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    let term: Vec<char> = term.trim().chars().collect();
+    let (Some(&first), Some(&last)) = (term.first(), term.last()) else {
+        return Vec::new();
+    };
+
+    let mut ranges = Vec::new();
+    let mut previous: Option<char> = None;
+    for (start, c) in text.char_indices() {
+        let starts_word = !needs_boundary(first) || is_boundary(previous);
+        previous = Some(c);
+        if !starts_word || !chars_match(c, first, case_sensitive) {
+            continue;
+        }
+        let Some(len) = match_at(text, start, &term, case_sensitive) else {
+            continue;
+        };
+        let end = start + len;
+        if !needs_boundary(last) || is_boundary(text[end..].chars().next()) {
+            ranges.push(start..end);
+        }
+    }
+    ranges
+}
+
+/// Whole-word matches of any of `terms`, in text order without overlaps. Where matches
+/// overlap the longest one wins, so "HubSpot CRM" is one match rather than "HubSpot".
+/// Matches inside an occurrence of one of `exclusions` (e.g. "black box" for "Box") are
+/// dropped. Acronyms are always matched case-sensitively; other terms only with
+/// `case_sensitive`. Exclusions are matched in any case.
+pub fn find_terms(text: &str, terms: &[&str], exclusions: &[&str], case_sensitive: bool) -> Vec<TermMatch> {
+    let excluded: Vec<Range<usize>> = exclusions.iter()
+        .flat_map(|e| find_term(text, e, false))
+        .collect();
+
+    let mut matches: Vec<TermMatch> = terms.iter()
+        .enumerate()
+        .flat_map(|(index, term)| {
+            find_term(text, term, case_sensitive || is_acronym(term))
+                .into_iter()
+                .map(move |range| TermMatch { range, term: index })
+        })
+        .filter(|m| !excluded.iter().any(|e| e.start <= m.range.start && m.range.end <= e.end))
+        .collect();
+
+    matches.sort_by(|a, b| a.range.start.cmp(&b.range.start).then(b.range.end.cmp(&a.range.end)));
+    let mut kept: Vec<TermMatch> = Vec::with_capacity(matches.len());
+    for m in matches {
+        if kept.last().is_none_or(|k| k.range.end <= m.range.start) {
+            kept.push(m);
+        }
+    }
+    kept
+}
+
+/// Spans with the JavaScript string indices of byte ranges in `text`, which must be in order
+pub fn to_spans<'a>(text: &str, ranges: impl IntoIterator<Item = &'a Range<usize>>) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    // UTF-16 offset of byte `position`, counted on from the previous range
+    let (mut position, mut offset) = (0, 0);
+    for range in ranges {
+        offset += text[position..range.start].encode_utf16().count();
+        let start = offset;
+        offset += text[range.clone()].encode_utf16().count();
+        position = range.end;
+        spans.push(TextSpan { start, end: offset, text: text[range.clone()].to_string() });
+    }
+    spans
+}
+
+/// Byte ranges of the sentences in `text`. A sentence ends at a line break, after ".",
+/// "!" or "?" followed by whitespace, or after "。", "！" or "？".
+pub fn sentences(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, n)| *n);
+        let end = match c {
+            '\n' => Some(i),
+            '。' | '！' | '？' => Some(i + c.len_utf8()),
+            '.' | '!' | '?' if next.is_none_or(char::is_whitespace) => Some(i + c.len_utf8()),
+            _ => None,
+        };
+        if let Some(end) = end {
+            if !text[start..end].trim().is_empty() {
+                ranges.push(start..end);
+            }
+            start = i + c.len_utf8();
+        }
+    }
+    if !text[start..].trim().is_empty() {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|r| &text[r.clone()]).collect()
+    }
+
+    #[test]
+    fn matches_whole_words_only() {
+        let text = "Notion is not notional; try Notion's API or NotionAI.";
+        assert_eq!(found(text, &find_term(text, "Notion", false)), vec!["Notion", "Notion"]);
+        assert!(find_term("unnotioned", "notion", false).is_empty());
+        assert!(find_term("Notion_db", "Notion", false).is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_unless_asked() {
+        let text = "notion and NOTION";
+        assert_eq!(find_term(text, "Notion", false).len(), 2);
+        assert!(find_term(text, "Notion", true).is_empty());
+    }
+
+    #[test]
+    fn edges_without_word_characters_need_no_boundary() {
+        let text = "We use C++11 and C#.";
+        assert_eq!(found(text, &find_term(text, "C++", false)), vec!["C++"]);
+        assert_eq!(found(text, &find_term(text, "C#", false)), vec!["C#"]);
+    }
+
+    #[test]
+    fn whitespace_and_apostrophes_are_normalized() {
+        let text = "Try HubSpot\n  CRM or McDonald\u{2019}s";
+        assert_eq!(found(text, &find_term(text, "HubSpot CRM", false)), vec!["HubSpot\n  CRM"]);
+        assert_eq!(found(text, &find_term(text, "McDonald's", false)), vec!["McDonald\u{2019}s"]);
+    }
+
+    #[test]
+    fn unspaced_scripts_count_as_boundaries() {
+        let text = "我推荐Notion和Asana";
+        assert_eq!(found(text, &find_term(text, "Notion", false)), vec!["Notion"]);
+        assert_eq!(found(text, &find_term(text, "Asana", false)), vec!["Asana"]);
+    }
+
+    #[test]
+    fn acronym_rules() {
+        assert!(is_acronym("HS"));
+        assert!(is_acronym("IBM"));
+        assert!(is_acronym("SAP-AG"));
+        assert!(!is_acronym("A"));
+        assert!(!is_acronym("Notion"));
+        assert!(!is_acronym("ABCDEF"));
+
+        // Acronyms only match in their own case, even without case sensitivity
+        let text = "HS is great, as is hubspot; hs alone doesn't count";
+        let matches = find_terms(text, &["HubSpot", "HS"], &[], false);
+        let ranges: Vec<Range<usize>> = matches.iter().map(|m| m.range.clone()).collect();
+        assert_eq!(found(text, &ranges), vec!["HS", "hubspot"]);
+    }
+
+    #[test]
+    fn longest_overlapping_match_wins() {
+        let text = "HubSpot CRM beats HubSpot Sales";
+        let matches = find_terms(text, &["HubSpot", "HubSpot CRM"], &[], false);
+        assert_eq!(matches, vec![
+            TermMatch { range: 0..11, term: 1 },
+            TermMatch { range: 18..25, term: 0 },
+        ]);
+    }
+
+    #[test]
+    fn exclusion_phrases_drop_matches_inside_them() {
+        let text = "A black box model, unlike Box, stores files. Box Office numbers too.";
+        let matches = find_terms(text, &["Box"], &["black box", "box office"], false);
+        let ranges: Vec<Range<usize>> = matches.iter().map(|m| m.range.clone()).collect();
+        assert_eq!(ranges, vec![26..29]);
+    }
+
+    #[test]
+    fn spans_use_utf16_offsets() {
+        // "😀" is one char, 4 bytes and 2 UTF-16 units; "é" is 2 bytes and 1 unit
+        let text = "😀 café Notion, 𝔘 Notion";
+        let ranges = find_term(text, "Notion", false);
+        assert_eq!(ranges, vec![11..17, 24..30]);
+        assert_eq!(to_spans(text, &ranges), vec![
+            TextSpan { start: 8, end: 14, text: "Notion".to_string() },
+            TextSpan { start: 19, end: 25, text: "Notion".to_string() },
+        ]);
+        assert_eq!(text.encode_utf16().skip(19).take(6).collect::<Vec<u16>>(), "Notion".encode_utf16().collect::<Vec<u16>>());
+    }
+
+    #[test]
+    fn sentence_boundaries() {
+        let text = "Notion is great. Asana v2.0 is fast!\nClickUp?\n\n飞书很好。钉钉也不错";
+        let parts: Vec<&str> = sentences(text).into_iter().map(|r| text[r].trim()).collect();
+        assert_eq!(parts, vec!["Notion is great.", "Asana v2.0 is fast!", "ClickUp?", "飞书很好。", "钉钉也不错"]);
+    }
+}
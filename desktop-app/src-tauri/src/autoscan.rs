@@ -1,4 +1,4 @@
-use crate::{storage, storage::ProductConfig, AppState};
+use crate::{scan_queue, storage, storage::{ProductConfig, ScanJobSource}, AppState, PromptFilter};
 use chrono::Timelike;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager, async_runtime};
@@ -147,82 +147,41 @@ async fn check_and_run_auto_scans(app: &AppHandle) {
         println!("[AutoScan] Product {}: time to run scan {} (scheduled for {}:00, current hour: {})",
             product_id, next_scheduled_index + 1, next_scheduled_hour, current_hour);
 
-        // Check if a scan is already running
-        {
-            let scan = state.scan.lock();
-            if scan.is_running {
-                println!("[AutoScan] Scan already in progress, will retry next check");
-                continue;
-            }
+        // Don't queue the same product twice while its scheduled scan is still waiting
+        let already_queued = storage::load_scan_queue().iter().any(|job| {
+            job.product_id == product_id && job.source == ScanJobSource::Scheduled
+        });
+        if already_queued {
+            println!("[AutoScan] Scheduled scan for product {} is already queued", product_id);
+            continue;
         }
 
-        // Run the scan
-        println!("[AutoScan] Starting scheduled scan {}/{} for product {}",
+        // No hourly-cap check here: each lane waits for its own account's turn, so an account
+        // at its cap doesn't hold back the accounts that still have budget
+
+        println!("[AutoScan] Queueing scheduled scan {}/{} for product {}",
             next_scheduled_index + 1, scheduled_times.len(), product_id);
 
-        match run_auto_scan(
+        match scan_queue::enqueue(
             app,
-            &state,
-            &product_id,
+            product_id.clone(),
+            config.ready_platforms.clone(),
             config.samples_per_prompt as usize,
-            &config.ready_platforms,
-        ).await {
-            Ok(_) => {
-                println!("[AutoScan] Scheduled scan {} for product {} completed successfully",
-                    next_scheduled_index + 1, product_id);
-
-                // Reload config and increment the counter
-                let mut updated_config = storage::get_product_config(&product_id);
-                updated_config.scans_today += 1;
-                updated_config.last_auto_scan_date = Some(today.clone());
-                let _ = storage::update_product_config(&product_id, &updated_config);
-            }
-            Err(e) => {
-                eprintln!("[AutoScan] Scheduled scan {} for product {} failed: {}",
-                    next_scheduled_index + 1, product_id, e);
-                // Still increment to avoid retrying failed scans indefinitely
-                let mut updated_config = storage::get_product_config(&product_id);
-                updated_config.scans_today += 1;
-                updated_config.last_auto_scan_date = Some(today.clone());
-                let _ = storage::update_product_config(&product_id, &updated_config);
-            }
+            PromptFilter::default(),
+            None,
+            ScanJobSource::Scheduled,
+        ) {
+            Ok(job) => println!("[AutoScan] Scheduled scan for product {} queued as {}", product_id, job.id),
+            Err(e) => eprintln!("[AutoScan] Failed to queue scheduled scan for product {}: {}", product_id, e),
         }
-    }
-
-    println!("[AutoScan] Auto-scan check complete");
-}
 
-/// Run a single auto-scan by invoking the command through Tauri
-async fn run_auto_scan(
-    app: &AppHandle,
-    state: &Arc<AppState>,
-    product_id: &str,
-    samples_per_prompt: usize,
-    platforms: &[String],
-) -> Result<(), String> {
-    use crate::commands::scan::start_scan_internal;
-
-    start_scan_internal(
-        product_id.to_string(),
-        Some(samples_per_prompt),
-        Some(platforms.to_vec()),
-        app.clone(),
-        state.clone(),
-    ).await?;
-
-    // Wait for the scan to complete
-    loop {
-        tokio::time::sleep(Duration::from_secs(5)).await;
-
-        let is_running = {
-            let scan = state.scan.lock();
-            scan.is_running
-        };
-
-        if !is_running {
-            break;
-        }
+        // Count the scan once it is queued so the next check doesn't queue it again
+        // (a scan that fails to start is not retried indefinitely either)
+        let mut updated_config = storage::get_product_config(&product_id);
+        updated_config.scans_today += 1;
+        updated_config.last_auto_scan_date = Some(today.clone());
+        let _ = storage::update_product_config(&product_id, &updated_config);
     }
 
-    Ok(())
+    println!("[AutoScan] Auto-scan check complete");
 }
@@ -1,8 +1,8 @@
-use crate::{AppState, Product, Prompt, ScanResult, SUPABASE_ANON_KEY, SUPABASE_URL};
+use crate::{analysis::Competitor, outbox::OutboxStatus, AppState, Product, Prompt, ScanResult, SUPABASE_ANON_KEY, SUPABASE_URL};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 // Cached AI platforms
 lazy_static::lazy_static! {
@@ -41,7 +41,7 @@ pub struct UserInfo {
 pub struct PromptsResponse {
     pub product: ProductInfo,
     pub prompts: Vec<Prompt>,
-    pub competitors: Vec<String>,
+    pub competitors: Vec<Competitor>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -53,6 +53,14 @@ pub struct ProductInfo {
     pub domain: Option<String>,
     #[serde(default)]
     pub domain_aliases: Option<Vec<String>>,
+    /// Other names the brand goes by
+    #[serde(default)]
+    pub brand_aliases: Vec<String>,
+    /// Phrases that contain the brand's name without meaning the brand
+    #[serde(default)]
+    pub brand_exclusions: Vec<String>,
+    #[serde(default)]
+    pub brand_case_sensitive: bool,
 }
 
 async fn api_request<T: serde::de::DeserializeOwned>(
@@ -140,6 +148,136 @@ pub async fn finalize_scan(
     .await
 }
 
+/// An upload the API did not accept
+#[derive(Debug)]
+pub struct UploadError {
+    pub message: String,
+    /// The API turned it down for good (e.g. a deleted product or an invalid payload),
+    /// so retrying it can't help
+    pub permanent: bool,
+}
+
+impl UploadError {
+    pub fn retryable(message: String) -> Self {
+        UploadError { message, permanent: false }
+    }
+
+    /// Client errors are permanent, except request timeouts and rate limits
+    async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let permanent = status.is_client_error()
+            && status != reqwest::StatusCode::REQUEST_TIMEOUT
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS;
+        let error_text = response.text().await.unwrap_or_default();
+        UploadError { message: format!("API error {}: {}", status, error_text), permanent }
+    }
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// POST a scan result to the API. Any non-2xx response counts as not accepted.
+/// The result's `result_key` lets the API recognize a retry whose first attempt did get
+/// stored (e.g. the response was lost to a timeout) and update it instead of adding another.
+pub async fn post_scan_result(token: &str, result: &ScanResult) -> Result<(), UploadError> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/functions/v1/extension-scan-results", SUPABASE_URL))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("apikey", SUPABASE_ANON_KEY)
+        .header("Content-Type", "application/json")
+        .json(result)
+        .send()
+        .await
+        .map_err(|e| UploadError::retryable(format!("Network error: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(UploadError::from_response(response).await);
+    }
+
+    Ok(())
+}
+
+/// POST the finalize call for a scan session
+pub async fn post_finalize_scan(token: &str, scan_session_id: &str, product_id: &str) -> Result<(), UploadError> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/functions/v1/extension-finalize-scan", SUPABASE_URL))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("apikey", SUPABASE_ANON_KEY)
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({
+            "scanSessionId": scan_session_id,
+            "productId": product_id
+        }))
+        .send()
+        .await
+        .map_err(|e| UploadError::retryable(format!("Network error: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(UploadError::from_response(response).await);
+    }
+
+    Ok(())
+}
+
+/// Storage bucket that receives DOM snapshots and screenshots
+const SNAPSHOT_BUCKET: &str = "scan-snapshots";
+
+/// Upload a DOM snapshot (`.html.gz`) or screenshot (`.png`) to `<product>/<session>/<file name>`
+/// in storage. Returns the object path inside the bucket.
+pub async fn upload_evidence_file(
+    state: &Arc<AppState>,
+    product_id: &str,
+    scan_session_id: &str,
+    path: &std::path::Path,
+) -> Result<String, String> {
+    let token = crate::commands::auth::ensure_valid_token(state).await?;
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid evidence file path")?;
+    let content_type = if file_name.ends_with(".png") { "image/png" } else { "application/gzip" };
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+    let object_path = format!("{}/{}/{}", product_id, scan_session_id, file_name);
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/storage/v1/object/{}/{}", SUPABASE_URL, SNAPSHOT_BUCKET, object_path))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("apikey", SUPABASE_ANON_KEY)
+        .header("Content-Type", content_type)
+        .header("x-upsert", "true")
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, error_text));
+    }
+
+    Ok(object_path)
+}
+
+/// Get how many uploads are waiting in the offline outbox
+#[tauri::command]
+pub async fn get_outbox_status() -> Result<OutboxStatus, String> {
+    Ok(crate::outbox::outbox_status())
+}
+
+/// Retry every pending upload right away, ignoring backoff
+#[tauri::command]
+pub async fn retry_outbox_now(app: AppHandle) -> Result<OutboxStatus, String> {
+    crate::outbox::flush_outbox(&app, true).await;
+    Ok(crate::outbox::outbox_status())
+}
+
 /// Fetch AI platforms from the database (public, no auth required)
 #[tauri::command]
 pub async fn get_ai_platforms(force_refresh: Option<bool>) -> Result<Vec<AIPlatform>, String> {
@@ -97,7 +97,7 @@ pub async fn open_country_login(
     }
 
     // Create webview manager
-    let mut manager = WebviewManager::new();
+    let manager = WebviewManager::new();
 
     // Create webview with country proxy and isolated data directory
     manager
@@ -152,7 +152,7 @@ pub async fn open_local_login(app: AppHandle, platform: String) -> Result<String
     }
 
     // Create webview manager
-    let mut manager = WebviewManager::new();
+    let manager = WebviewManager::new();
 
     // Create webview with isolated data directory (no proxy)
     manager
@@ -219,7 +219,7 @@ pub async fn open_magic_link(
         }
     }
 
-    let mut manager = WebviewManager::new();
+    let manager = WebviewManager::new();
 
     if is_local {
         manager
@@ -306,7 +306,7 @@ async fn authenticate_single(
     let label = format!("bulk-auth-{}-{}", region, platform);
 
     // Create webview manager
-    let mut manager = WebviewManager::new();
+    let manager = WebviewManager::new();
 
     // Create webview with country proxy
     // In debug builds, make webviews visible for debugging
@@ -0,0 +1,122 @@
+use crate::commands::api::upload_evidence_file;
+use crate::export::{self, ExportFormat};
+use crate::reanalysis::{self, ReanalysisSummary};
+use crate::scan_history::{self, HistoryEntry, HistoryQuery, HistorySession};
+use crate::{storage, AppState};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Query locally stored scan results by product, session, prompt, platform, country and date range
+#[tauri::command]
+pub async fn query_scan_history(query: Option<HistoryQuery>) -> Result<Vec<HistoryEntry>, String> {
+    scan_history::query_results(&query.unwrap_or_default())
+}
+
+/// List scan sessions in the local history, optionally for one product
+#[tauri::command]
+pub async fn get_history_sessions(product_id: Option<String>) -> Result<Vec<HistorySession>, String> {
+    scan_history::list_sessions(product_id.as_deref())
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub rows: usize,
+}
+
+/// Export every result of a scan session, or of a date range (unix seconds), to CSV, JSONL or Parquet
+#[tauri::command]
+pub async fn export_scan_session(
+    format: ExportFormat,
+    path: String,
+    scan_session_id: Option<String>,
+    product_id: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<ExportSummary, String> {
+    if scan_session_id.is_none() && from.is_none() && to.is_none() {
+        return Err("Specify a scan session or a date range to export".to_string());
+    }
+
+    let query = HistoryQuery {
+        scan_session_id,
+        product_id,
+        from,
+        to,
+        ..Default::default()
+    };
+
+    // History is returned newest first; exports read better in collection order
+    let mut entries = scan_history::query_results(&query)?;
+    entries.reverse();
+
+    if entries.is_empty() {
+        return Err("No results to export".to_string());
+    }
+
+    let path = PathBuf::from(path);
+    export::write_export(&entries, format, &path)?;
+
+    println!("[Export] Wrote {} results as {:?} to {:?}", entries.len(), format, path);
+    Ok(ExportSummary {
+        path: path.to_string_lossy().to_string(),
+        rows: entries.len(),
+    })
+}
+
+/// Upload every archived DOM snapshot of a scan session. Returns how many were uploaded.
+#[tauri::command]
+pub async fn upload_scan_snapshots(
+    scan_session_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<usize, String> {
+    let dir = storage::get_snapshot_dir(&scan_session_id);
+    let files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|_| "No snapshots stored for this scan session".to_string())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".html.gz"))
+        .collect();
+
+    // The checkpoint may already be pruned; the local history knows the product too
+    let product_id = storage::load_scan_checkpoint(&scan_session_id)
+        .map(|c| c.product_id)
+        .or_else(|| {
+            let query = HistoryQuery {
+                scan_session_id: Some(scan_session_id.clone()),
+                limit: Some(1),
+                ..Default::default()
+            };
+            scan_history::query_results(&query).ok()?.into_iter().next().map(|e| e.result.product_id)
+        })
+        .ok_or("Unknown product for this scan session")?;
+
+    let mut uploaded = 0;
+    for path in &files {
+        match upload_evidence_file(state.inner(), &product_id, &scan_session_id, path).await {
+            Ok(_) => uploaded += 1,
+            Err(e) => eprintln!("[Snapshot] Failed to upload {:?}: {}", path, e),
+        }
+    }
+
+    println!("[Snapshot] Uploaded {}/{} snapshots of session {}", uploaded, files.len(), scan_session_id);
+    if uploaded < files.len() {
+        return Err(format!("Uploaded {} of {} snapshots", uploaded, files.len()));
+    }
+    Ok(uploaded)
+}
+
+/// Re-run mention, competitor, citation and credits detection over a stored session with the
+/// current extraction rules. Corrected results replace the stored ones and, with `resubmit`,
+/// are sent to the API again.
+#[tauri::command]
+pub async fn reanalyze_session(
+    scan_session_id: String,
+    resubmit: Option<bool>,
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ReanalysisSummary, String> {
+    reanalysis::reanalyze_session(&app, state.inner(), &scan_session_id, resubmit.unwrap_or(false)).await
+}
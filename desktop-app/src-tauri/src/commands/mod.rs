@@ -3,7 +3,9 @@ pub mod auth;
 pub mod autologin;
 pub mod bulk_auth;
 pub mod credentials;
+pub mod history;
 pub mod platform;
 pub mod proxy;
+pub mod queue;
 pub mod scan;
 pub mod settings;
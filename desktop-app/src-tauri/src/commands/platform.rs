@@ -71,12 +71,14 @@ pub async fn close_platform_login(platform: String, app: AppHandle) -> Result<()
     Ok(())
 }
 
-fn platform_display_name(platform: &str) -> &str {
+pub fn platform_display_name(platform: &str) -> &str {
     match platform {
         "chatgpt" => "ChatGPT",
         "claude" => "Claude",
         "gemini" => "Gemini",
         "perplexity" => "Perplexity",
+        "google_aio" => "Google AI Overviews",
+        "google_ai_mode" => "Google AI Mode",
         _ => platform,
     }
 }
@@ -0,0 +1,51 @@
+use crate::{
+    commands::scan::default_scan_platforms,
+    scan_queue::{self, ScanQueueStatus},
+    storage::{self, ScanJob, ScanJobSource},
+    PromptFilter,
+};
+use tauri::AppHandle;
+
+/// Add a scan to the queue. It starts as soon as every job ahead of it has finished.
+#[tauri::command]
+pub async fn enqueue_scan(
+    product_id: String,
+    samples_per_prompt: Option<usize>,
+    platforms: Option<Vec<String>>,
+    prompt_filter: Option<PromptFilter>,
+    priority: Option<i32>,
+    app: AppHandle,
+) -> Result<ScanJob, String> {
+    scan_queue::enqueue(
+        &app,
+        product_id,
+        platforms.unwrap_or_else(default_scan_platforms),
+        samples_per_prompt.unwrap_or(1),
+        prompt_filter.unwrap_or_default(),
+        priority,
+        ScanJobSource::Manual,
+    )
+}
+
+#[tauri::command]
+pub async fn get_scan_queue() -> Result<ScanQueueStatus, String> {
+    Ok(scan_queue::queue_status())
+}
+
+/// Move the given jobs to the front of their priority group, in the given order
+#[tauri::command]
+pub async fn reorder_scan_queue(job_ids: Vec<String>, app: AppHandle) -> Result<ScanQueueStatus, String> {
+    storage::reorder_scan_queue(&job_ids)?;
+    scan_queue::emit_queue_status(&app);
+    Ok(scan_queue::queue_status())
+}
+
+/// Remove a job that has not started yet
+#[tauri::command]
+pub async fn remove_scan_job(job_id: String, app: AppHandle) -> Result<(), String> {
+    if !storage::remove_scan_job(&job_id)? {
+        return Err("Scan job not found in queue".to_string());
+    }
+    scan_queue::emit_queue_status(&app);
+    Ok(())
+}
@@ -1,13 +1,21 @@
 use crate::{
-    commands::api::get_platform_url,
-    storage,
-    update_tray_status, webview::WebviewManager, AppState, PlatformState, Prompt, ScanComplete,
-    ScanProgress, ScanResult,
+    analysis::{competitors, sentiment, BrandTerms, Competitor},
+    commands::api::{get_platform_url, upload_evidence_file, PromptsResponse},
+    storage::{self, ScanCell, ScanCellStatus, ScanCheckpoint, ScanJob, ScanJobSource, ScanJobStatus, SnapshotMode},
+    notifications::{self, NotificationKind}, prompt_template, rate_limit, scan_queue, update_tray_status, webhooks,
+    webview::{completion_timeout_secs, supports_follow_ups, AnswerRenderError, CollectResponse, WebviewManager, ANSWER_RENDER_SUPPORTED},
+    AppState, Citation, CompetitorCount, CompetitorDetailResult, ConversationTurn, PlatformState, Prompt,
+    PromptFilter, ScanBreakdown, ScanComplete, ScanProgress, ScanResult,
 };
+use parking_lot::Mutex;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::{AppHandle, Emitter, State};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
 #[derive(Clone, Serialize)]
@@ -20,29 +28,62 @@ pub struct ScanProgressEvent {
     pub countdown_seconds: Option<usize>,
 }
 
+/// Queue a manual scan, optionally limited to a subset of prompts.
+/// It starts right away unless another scan is running; the returned job's status says
+/// which, so the UI only shows progress for a scan that is actually its own.
 #[tauri::command]
 pub async fn start_scan(
     product_id: String,
     samples_per_prompt: Option<usize>,
     platforms: Option<Vec<String>>,
+    prompt_filter: Option<PromptFilter>,
     app: AppHandle,
-    state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    start_scan_internal(product_id, samples_per_prompt, platforms, app, state.inner().clone()).await
+) -> Result<ScanJob, String> {
+    let mut job = crate::scan_queue::enqueue(
+        &app,
+        product_id,
+        platforms.unwrap_or_else(default_scan_platforms),
+        samples_per_prompt.unwrap_or(1),
+        prompt_filter.unwrap_or_default(),
+        None,
+        ScanJobSource::Manual,
+    )?;
+
+    // The runner picks a job up within moments when nothing else is running
+    for _ in 0..QUEUE_PICKUP_POLLS {
+        if let Some(current) = crate::scan_queue::current_job() {
+            if current.id == job.id {
+                job.status = ScanJobStatus::Running;
+            }
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(QUEUE_PICKUP_POLL_MS)).await;
+    }
+
+    Ok(job)
+}
+
+/// How long `start_scan` waits for the queue runner to start a new job, in polls
+const QUEUE_PICKUP_POLLS: usize = 20;
+const QUEUE_PICKUP_POLL_MS: u64 = 50;
+
+/// Platforms scanned when none are specified
+pub fn default_scan_platforms() -> Vec<String> {
+    vec!["chatgpt".to_string(), "claude".to_string(), "gemini".to_string(), "perplexity".to_string(), "google_aio".to_string()]
 }
 
-/// Internal scan function that can be called without Tauri State wrapper
+/// Internal scan function that can be called without Tauri State wrapper.
+/// Returns the new scan session ID.
 pub async fn start_scan_internal(
     product_id: String,
     samples_per_prompt: Option<usize>,
     platforms: Option<Vec<String>>,
+    prompt_filter: PromptFilter,
     app: AppHandle,
     state: Arc<AppState>,
-) -> Result<(), String> {
+) -> Result<String, String> {
     // Default to common platforms if none specified
-    let selected_platforms: Vec<String> = platforms.unwrap_or_else(|| {
-        vec!["chatgpt".to_string(), "claude".to_string(), "gemini".to_string(), "perplexity".to_string(), "google_aio".to_string()]
-    });
+    let selected_platforms: Vec<String> = platforms.unwrap_or_else(default_scan_platforms);
     // Check if scan is already running
     {
         let scan = state.scan.lock();
@@ -51,54 +92,135 @@ pub async fn start_scan_internal(
         }
     }
 
-    // Ensure we have a valid auth token (refresh if expired)
-    let token = crate::commands::auth::ensure_valid_token(&state).await?;
-
-    // Get prompts from API
-    let prompts_response: crate::commands::api::PromptsResponse = {
+    let prompts_response = fetch_scan_prompts(&state, &product_id, &prompt_filter).await?;
 
-        let client = reqwest::Client::new();
-        let url = format!(
-            "{}/functions/v1/extension-prompts?productId={}",
-            crate::SUPABASE_URL,
-            product_id
-        );
+    // Debug: Log received prompts and their target_regions
+    eprintln!("[Scan] Received {} prompts from API:", prompts_response.prompts.len());
+    for (i, prompt) in prompts_response.prompts.iter().enumerate() {
+        eprintln!("[Scan]   Prompt {}: id={}, target_regions={:?}", i, prompt.id, prompt.target_regions);
+    }
 
-        let response = client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("apikey", crate::SUPABASE_ANON_KEY)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch prompts: {}", e))?;
+    let samples = samples_per_prompt.unwrap_or(1);
+    let scan_countries = scan_countries_for_prompts(&prompts_response.prompts);
 
-        if !response.status().is_success() {
-            return Err("Failed to fetch prompts".to_string());
-        }
+    eprintln!("[Scan] Scan countries (from prompt target_regions): {:?}", scan_countries);
 
-        response.json().await.map_err(|e| format!("Parse error: {}", e))?
+    let product_config = storage::get_product_config(&product_id);
+    let brand = BrandTerms::for_product(&prompts_response.product, &product_config);
+    let competitors = competitors::with_local_terms(prompts_response.competitors, &product_config.competitor_terms);
+
+    // Build the full cell matrix up front so progress can be checkpointed to disk
+    let now = chrono::Utc::now().timestamp();
+    let mut checkpoint = ScanCheckpoint {
+        scan_session_id: Uuid::new_v4().to_string(),
+        product_id,
+        samples,
+        platforms: selected_platforms,
+        countries: scan_countries,
+        prompts: prompts_response.prompts,
+        brand: brand.name,
+        brand_aliases: brand.aliases,
+        brand_exclusions: brand.exclusions,
+        brand_case_sensitive: brand.case_sensitive,
+        brand_domain: prompts_response.product.domain,
+        domain_aliases: prompts_response.product.domain_aliases,
+        competitors,
+        cells: Vec::new(),
+        status: "running".to_string(),
+        started_at: now,
+        updated_at: now,
     };
+    checkpoint.cells = build_scan_cells(&checkpoint);
+
+    eprintln!("[Scan] Total cells (prompt executions x samples x platforms): {} (base prompts: {})",
+        checkpoint.cells.len(), checkpoint.prompts.len());
+
+    let scan_session_id = checkpoint.scan_session_id.clone();
+    launch_scan(app, state, checkpoint)?;
+    Ok(scan_session_id)
+}
+
+/// Fetch the product's prompts via `extension-prompts` and apply the scan's prompt filter
+async fn fetch_scan_prompts(
+    state: &Arc<AppState>,
+    product_id: &str,
+    prompt_filter: &PromptFilter,
+) -> Result<PromptsResponse, String> {
+    let mut prompts_response = fetch_product_prompts(state, product_id).await?;
 
     if prompts_response.prompts.is_empty() {
         return Err("No prompts found for this product".to_string());
     }
 
-    // Debug: Log received prompts and their target_regions
-    eprintln!("[Scan] Received {} prompts from API:", prompts_response.prompts.len());
-    for (i, prompt) in prompts_response.prompts.iter().enumerate() {
-        eprintln!("[Scan]   Prompt {}: id={}, target_regions={:?}", i, prompt.id, prompt.target_regions);
+    prompts_response.prompts.retain(|p| prompt_filter.matches(p));
+    prompts_response.prompts.retain_mut(|p| prompt_filter.restrict_regions(p));
+
+    if prompt_filter.only_failed_last_session {
+        let failed = failed_prompts_of_last_session(state, product_id)?;
+        prompts_response.prompts.retain(|p| failed.contains(&p.id));
     }
 
-    let samples = samples_per_prompt.unwrap_or(1);
-    let scan_session_id = Uuid::new_v4().to_string();
-    let platform_count = selected_platforms.len();
+    if prompts_response.prompts.is_empty() {
+        return Err("No prompts match the scan filter".to_string());
+    }
+
+    Ok(prompts_response)
+}
+
+/// Fetch the product's brand, competitors and prompts via `extension-prompts`
+pub async fn fetch_product_prompts(state: &Arc<AppState>, product_id: &str) -> Result<PromptsResponse, String> {
+    // Ensure we have a valid auth token (refresh if expired)
+    let token = crate::commands::auth::ensure_valid_token(state).await?;
+
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/functions/v1/extension-prompts?productId={}",
+        crate::SUPABASE_URL,
+        product_id
+    );
+
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("apikey", crate::SUPABASE_ANON_KEY)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch prompts: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err("Failed to fetch prompts".to_string());
+    }
 
-    // Collect all unique regions from prompts
-    // Each prompt can have target_regions array specifying where it should be tested
-    let mut all_regions: std::collections::HashSet<String> = std::collections::HashSet::new();
-    for prompt in &prompts_response.prompts {
+    response.json().await.map_err(|e| format!("Parse error: {}", e))
+}
+
+/// Prompt IDs with at least one failed or timed-out cell in the product's most recent scan
+/// session, including one interrupted while running. Limited and skipped cells don't count:
+/// limited prompts were already re-queued, and skipped ones never ran.
+fn failed_prompts_of_last_session(state: &AppState, product_id: &str) -> Result<HashSet<String>, String> {
+    let live_session = {
+        let scan = state.scan.lock();
+        scan.is_running.then(|| scan.scan_session_id.clone()).flatten()
+    };
+    let last_session = storage::list_scan_checkpoints()
+        .into_iter()
+        .find(|c| c.product_id == product_id && Some(&c.scan_session_id) != live_session.as_ref())
+        .ok_or("No previous scan session found for this product")?;
+
+    Ok(last_session.cells
+        .iter()
+        .filter(|c| c.status == ScanCellStatus::Failed || c.response_timed_out == Some(true))
+        .map(|c| c.prompt_id.clone())
+        .collect())
+}
+
+/// Collect all unique regions from prompts.
+/// Each prompt can have target_regions array specifying where it should be tested;
+/// prompts without regions run in "local".
+fn scan_countries_for_prompts(prompts: &[Prompt]) -> Vec<String> {
+    let mut all_regions: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    for prompt in prompts {
         if prompt.target_regions.is_empty() {
-            // No regions specified for this prompt - use "local"
             all_regions.insert("local".to_string());
         } else {
             for region in &prompt.target_regions {
@@ -107,580 +229,1567 @@ pub async fn start_scan_internal(
         }
     }
 
-    // Convert to Vec for iteration
-    let scan_countries: Vec<String> = if all_regions.is_empty() {
+    if all_regions.is_empty() {
         vec!["local".to_string()]
     } else {
         all_regions.into_iter().collect()
-    };
+    }
+}
 
-    eprintln!("[Scan] Scan countries (from prompt target_regions): {:?}", scan_countries);
+/// Expand countries × platforms × prompts × samples into checkpoint cells.
+/// Each prompt runs once per target region (or once in "local" if no regions are specified).
+fn build_scan_cells(checkpoint: &ScanCheckpoint) -> Vec<ScanCell> {
+    let mut cells = Vec::new();
+    for country_code in &checkpoint.countries {
+        for platform in &checkpoint.platforms {
+            for (_, prompt) in prompts_for_country(&checkpoint.prompts, country_code) {
+                for sample in 0..checkpoint.samples {
+                    cells.push(ScanCell {
+                        country: country_code.clone(),
+                        platform: platform.clone(),
+                        prompt_id: prompt.id.clone(),
+                        sample,
+                        status: ScanCellStatus::Pending,
+                        brand_mentioned: None,
+                        citation_present: None,
+                        credits_exhausted: None,
+                        competitor_mentions: Vec::new(),
+                        response_timed_out: None,
+                    });
+                }
+            }
+        }
+    }
+    cells
+}
 
-    // Calculate total prompt executions accounting for regional targeting
-    // Each prompt runs once per target region (or once for "local" if no regions specified)
-    let mut total_prompt_executions: usize = 0;
-    for prompt in &prompts_response.prompts {
-        if prompt.target_regions.is_empty() {
-            // No regions specified - runs once in "local"
-            total_prompt_executions += 1;
+/// Prompts (with their index) that should run in a country.
+/// Prompts without target regions only run in "local".
+fn prompts_for_country<'a>(prompts: &'a [Prompt], country_code: &str) -> Vec<(usize, &'a Prompt)> {
+    let is_local = country_code == "local";
+    prompts.iter().enumerate().filter(|(_, p)| {
+        if p.target_regions.is_empty() {
+            is_local
         } else {
-            // Runs once per target region
-            total_prompt_executions += prompt.target_regions.len();
+            p.target_regions.iter().any(|r| r.to_lowercase() == country_code.to_lowercase())
         }
-    }
-    eprintln!("[Scan] Total prompt executions (with regions): {} (base prompts: {})", total_prompt_executions, prompts_response.prompts.len());
+    }).collect()
+}
 
+/// Initialize scan state from a checkpoint and run the scan in the background.
+/// Used for fresh scans as well as resumed sessions.
+fn launch_scan(app: AppHandle, state: Arc<AppState>, checkpoint: ScanCheckpoint) -> Result<(), String> {
     // Initialize scan state
     {
         let mut scan = state.scan.lock();
+        if scan.is_running {
+            return Err("Scan already in progress".to_string());
+        }
         scan.is_running = true;
+        scan.is_paused = false;
         scan.phase = "initializing".to_string();
-        scan.scan_session_id = Some(scan_session_id.clone());
-        scan.product_id = Some(product_id.clone());
+        scan.scan_session_id = Some(checkpoint.scan_session_id.clone());
+        scan.product_id = Some(checkpoint.product_id.clone());
         // Total = prompt executions × samples × platforms
-        scan.total_prompts = total_prompt_executions * samples * platform_count;
-        scan.completed_prompts = 0;
+        scan.total_prompts = checkpoint.cells.len();
+        scan.completed_prompts = checkpoint.cells.iter()
+            .filter(|c| c.status == ScanCellStatus::Collected)
+            .count();
 
         // Initialize platform states for selected platforms only
-        // Each platform will process all prompt executions
+        // Cells collected by a previous run of this session count as done
         scan.platforms.clear();
-        for platform in &selected_platforms {
+        for platform in &checkpoint.platforms {
+            let platform_cells: Vec<&ScanCell> = checkpoint.cells.iter()
+                .filter(|c| &c.platform == platform)
+                .collect();
+            let collected = platform_cells.iter()
+                .filter(|c| c.status == ScanCellStatus::Collected)
+                .count();
             scan.platforms.insert(
                 platform.clone(),
                 PlatformState {
                     status: "pending".to_string(),
-                    total: total_prompt_executions * samples,
-                    submitted: 0,
-                    collected: 0,
+                    total: platform_cells.len(),
+                    submitted: collected,
+                    collected,
                     failed: 0,
+                    ..Default::default()
                 },
             );
         }
     }
 
+    if let Err(e) = storage::save_scan_checkpoint(&checkpoint) {
+        eprintln!("[Scan] Failed to write checkpoint: {}", e);
+    }
+
     // Update tray to show scanning
     update_tray_status(&app, true);
 
     // Emit initial progress
     emit_progress_with_state(&app, &state);
-
-    // Clone necessary data for async task
-    let state_clone = state.clone();
-    let app_clone = app.clone();
-    let prompts = prompts_response.prompts.clone();
-    let brand = prompts_response.product.brand.clone();
-    let brand_domain = prompts_response.product.domain.clone();
-    let domain_aliases = prompts_response.product.domain_aliases.clone();
-    let competitors = prompts_response.competitors.clone();
-    let platforms_for_scan = selected_platforms.clone();
-    let countries_for_scan = scan_countries.clone();
+    notify_progress_milestone(&state, "started", None);
 
     // Spawn scan task
     tokio::spawn(async move {
-        let result = run_scan(
-            app_clone.clone(),
-            state_clone.clone(),
-            prompts,
-            samples,
-            scan_session_id.clone(),
-            product_id.clone(),
-            brand,
-            brand_domain,
-            domain_aliases,
-            competitors,
-            platforms_for_scan,
-            countries_for_scan,
-        )
-        .await;
+        let scan_session_id = checkpoint.scan_session_id.clone();
+        let result = run_scan(app.clone(), state.clone(), checkpoint).await;
 
         // Handle completion or error
-        match result {
+        let final_status = match result {
             Ok(stats) => {
-                let _ = app_clone.emit("scan:complete", stats);
+                webhooks::notify("scan:complete", Some(&scan_session_id), &stats);
+                notifications::notify(
+                    &app,
+                    NotificationKind::ScanComplete,
+                    &scan_session_id,
+                    "Scan complete",
+                    &format!("Your brand was mentioned in {:.0}% of {} responses",
+                        stats.mention_rate, stats.successful_prompts),
+                );
+                let _ = app.emit("scan:complete", stats);
+                "complete"
             }
             Err(e) => {
-                let _ = app_clone.emit("scan:error", e.clone());
+                webhooks::notify("scan:error", Some(&scan_session_id), serde_json::json!({ "error": e }));
+                if e != "Scan cancelled" {
+                    notifications::notify(&app, NotificationKind::ScanFailed, &scan_session_id, "Scan failed", &e);
+                }
+                let _ = app.emit("scan:error", e.clone());
                 eprintln!("Scan error: {}", e);
+                if e == "Scan cancelled" { "cancelled" } else { "failed" }
+            }
+        };
+
+        // Record how the session ended; cancelled and failed sessions stay resumable
+        if let Some(mut checkpoint) = storage::load_scan_checkpoint(&scan_session_id) {
+            checkpoint.status = final_status.to_string();
+            checkpoint.updated_at = chrono::Utc::now().timestamp();
+            if let Err(e) = storage::save_scan_checkpoint(&checkpoint) {
+                eprintln!("[Scan] Failed to write checkpoint: {}", e);
             }
         }
+        storage::prune_scan_checkpoints();
 
         // Reset tray to normal
-        update_tray_status(&app_clone, false);
+        update_tray_status(&app, false);
 
         // Reset scan state
-        let mut scan = state_clone.scan.lock();
+        let mut scan = state.scan.lock();
         scan.is_running = false;
+        scan.is_paused = false;
         scan.phase = "complete".to_string();
     });
 
     Ok(())
 }
 
-async fn run_scan(
+/// Update a cell in the checkpoint
+fn checkpoint_cell(
+    checkpoint: &mut ScanCheckpoint,
+    country_code: &str,
+    platform: &str,
+    prompt_id: &str,
+    sample: usize,
+    status: ScanCellStatus,
+) {
+    if let Some(cell) = checkpoint.cell_mut(country_code, platform, prompt_id, sample) {
+        cell.status = status;
+    }
+    checkpoint.updated_at = chrono::Utc::now().timestamp();
+}
+
+/// Mark every unfinished cell of a country/platform lane as skipped
+fn checkpoint_skip_lane(checkpoint: &mut ScanCheckpoint, country_code: &str, platform: &str) {
+    for cell in checkpoint.cells.iter_mut() {
+        if cell.country == country_code && cell.platform == platform && cell.status != ScanCellStatus::Collected {
+            cell.status = ScanCellStatus::Skipped;
+        }
+    }
+    checkpoint.updated_at = chrono::Utc::now().timestamp();
+}
+
+/// Mark the lane's cells that have not run yet as failed
+fn checkpoint_fail_lane(checkpoint: &mut ScanCheckpoint, country_code: &str, platform: &str) {
+    for cell in checkpoint.cells.iter_mut() {
+        if cell.country == country_code && cell.platform == platform && cell.status == ScanCellStatus::Pending {
+            cell.status = ScanCellStatus::Failed;
+        }
+    }
+    checkpoint.updated_at = chrono::Utc::now().timestamp();
+}
+
+/// Mark the lane's cells that have not run yet as limited and return their prompt IDs
+fn checkpoint_limit_lane(checkpoint: &mut ScanCheckpoint, country_code: &str, platform: &str) -> Vec<String> {
+    let mut prompt_ids: Vec<String> = Vec::new();
+    for cell in checkpoint.cells.iter_mut() {
+        if cell.country == country_code && cell.platform == platform && cell.status == ScanCellStatus::Pending {
+            cell.status = ScanCellStatus::Limited;
+            if !prompt_ids.contains(&cell.prompt_id) {
+                prompt_ids.push(cell.prompt_id.clone());
+            }
+        }
+    }
+    checkpoint.updated_at = chrono::Utc::now().timestamp();
+    prompt_ids
+}
+
+/// Everything the lanes of a running scan share
+struct ScanRun {
     app: AppHandle,
     state: Arc<AppState>,
-    prompts: Vec<Prompt>,
-    samples: usize,
+    manager: WebviewManager,
+    checkpoint: Mutex<ScanCheckpoint>,
+    /// Set when the checkpoint changed since it was last written to disk
+    checkpoint_dirty: AtomicBool,
+    /// Caps the number of scan webviews open at the same time across all lanes
+    webview_slots: Arc<Semaphore>,
+    /// Lanes still running per platform; a platform is done when its count reaches zero
+    open_lanes: Mutex<HashMap<String, usize>>,
+    /// Lanes whose account hit a usage limit, keyed by account, with the reset time if known
+    limited_lanes: Mutex<HashMap<String, Option<i64>>>,
+    snapshot_mode: SnapshotMode,
+    upload_snapshots: bool,
+    capture_screenshots: bool,
+    upload_screenshots: bool,
+    /// Set if answer renders can't work here, or once the webview refuses to export one,
+    /// so later cells don't retry
+    render_unsupported: AtomicBool,
     scan_session_id: String,
     product_id: String,
-    brand: String,
+    /// The session's prompts per country, with template variables expanded
+    prompts: HashMap<String, Vec<Prompt>>,
+    samples: usize,
+    brand: BrandTerms,
     brand_domain: Option<String>,
     domain_aliases: Option<Vec<String>>,
-    competitors: Vec<String>,
-    selected_platforms: Vec<String>,
-    scan_countries: Vec<String>,
+    competitors: Vec<Competitor>,
+}
+
+impl ScanRun {
+    fn is_cancelled(&self) -> bool {
+        !self.state.scan.lock().is_running
+    }
+
+    /// Hold a lane at a cell boundary while the scan is paused.
+    /// Returns false if the scan was cancelled.
+    async fn wait_while_paused(&self) -> bool {
+        loop {
+            {
+                let scan = self.state.scan.lock();
+                if !scan.is_running {
+                    return false;
+                }
+                if !scan.is_paused {
+                    return true;
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Take a webview slot once the scan isn't paused, so a paused lane holds none.
+    /// Returns None if the scan was cancelled.
+    async fn acquire_webview_slot(&self) -> Option<OwnedSemaphorePermit> {
+        loop {
+            if !self.wait_while_paused().await {
+                return None;
+            }
+            let slot = self.webview_slots.clone().acquire_owned().await.ok()?;
+            // Paused while waiting for the slot: give it back until resumed
+            if !self.state.scan.lock().is_paused {
+                return Some(slot);
+            }
+        }
+    }
+
+    /// Change the checkpoint; the change is written to disk by the next `save_checkpoint`
+    fn update_checkpoint<R>(&self, update: impl FnOnce(&mut ScanCheckpoint) -> R) -> R {
+        let result = update(&mut self.checkpoint.lock());
+        self.checkpoint_dirty.store(true, Ordering::SeqCst);
+        result
+    }
+
+    /// Write the checkpoint to disk if it changed since the last write, with the session
+    /// marked paused while it is, so a crash during a pause doesn't resume on its own.
+    /// Serializes a copy, so lanes aren't held up on the checkpoint lock during I/O.
+    fn save_checkpoint(&self) {
+        let status = if self.state.scan.lock().is_paused { "paused" } else { "running" };
+        {
+            let mut checkpoint = self.checkpoint.lock();
+            if checkpoint.status != status {
+                checkpoint.status = status.to_string();
+                checkpoint.updated_at = chrono::Utc::now().timestamp();
+                self.checkpoint_dirty.store(true, Ordering::SeqCst);
+            }
+        }
+        if !self.checkpoint_dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let checkpoint = self.checkpoint.lock().clone();
+        if let Err(e) = storage::save_scan_checkpoint(&checkpoint) {
+            eprintln!("[Scan] Failed to write checkpoint: {}", e);
+            self.checkpoint_dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Update a platform's progress counters and notify the UI
+    fn update_platform(&self, platform: &str, update: impl FnOnce(&mut PlatformState)) {
+        {
+            let mut scan = self.state.scan.lock();
+            if let Some(ps) = scan.platforms.get_mut(platform) {
+                update(ps);
+            }
+        }
+        emit_progress_with_state(&self.app, &self.state);
+    }
+
+    /// Stop a lane from submitting more prompts because its account hit a usage limit.
+    /// `notice` is the platform's message, which may say when the limit resets.
+    fn mark_lane_limited(&self, country_code: &str, platform: &str, notice: &str) {
+        let resets_at = rate_limit::parse_limit_reset(notice, chrono::Local::now());
+        eprintln!("[Scan] {} ({}) hit a usage limit (resets at {:?}): {}", platform, country_code, resets_at, notice);
+
+        self.limited_lanes.lock()
+            .entry(rate_limit::account_key(country_code, platform))
+            .and_modify(|r| *r = (*r).max(resets_at))
+            .or_insert(resets_at);
+
+        self.update_platform(platform, |ps| {
+            if !ps.limited_countries.iter().any(|c| c == country_code) {
+                ps.limited_countries.push(country_code.to_string());
+            }
+            ps.limited_until = ps.limited_until.max(resets_at);
+            ps.status = "limited".to_string();
+        });
+
+        let retry_at = resets_at.unwrap_or_else(|| chrono::Utc::now().timestamp() + LIMIT_FALLBACK_DEFER_SECS);
+        let retry_at = chrono::DateTime::from_timestamp(retry_at, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+            .unwrap_or_default();
+        notifications::notify(
+            &self.app,
+            NotificationKind::CreditsExhausted,
+            &rate_limit::account_key(country_code, platform),
+            &format!("{} is out of credits", notifications::lane_label(country_code, platform)),
+            &format!("Its remaining prompts will be retried after {}", retry_at),
+        );
+    }
+
+    /// `Some(reset time)` if the lane's account hit a usage limit during this scan
+    fn lane_limit(&self, country_code: &str, platform: &str) -> Option<Option<i64>> {
+        self.limited_lanes.lock().get(&rate_limit::account_key(country_code, platform)).copied()
+    }
+
+    /// Mark a lane as finished. Once every lane of a platform is done the platform is
+    /// complete, limited if an account hit a usage limit, or skipped if none of its lanes got to run.
+    fn finish_lane(&self, platform: &str) {
+        let remaining = {
+            let mut open_lanes = self.open_lanes.lock();
+            let count = open_lanes.entry(platform.to_string()).or_insert(1);
+            *count = count.saturating_sub(1);
+            *count
+        };
+        if remaining == 0 {
+            self.update_platform(platform, |ps| {
+                ps.status = if !ps.limited_countries.is_empty() {
+                    "limited".to_string()
+                } else if ps.submitted == 0 && ps.collected == 0 && ps.failed == 0 {
+                    "skipped".to_string()
+                } else {
+                    "complete".to_string()
+                };
+            });
+            notify_progress_milestone(&self.state, "platform_finished", Some(platform));
+        }
+    }
+
+    fn webview_label(&self, country_code: &str, platform: &str, prompt_idx: usize, sample: usize) -> String {
+        // Include scan_session_id, country prefix to avoid label conflicts between scans
+        format!("scan-{}-{}-{}-{}-{}", &self.scan_session_id[..8], country_code, platform, prompt_idx, sample)
+    }
+}
+
+/// A submitted prompt whose webview is waiting to be collected
+struct SubmittedCell {
+    label: String,
+    country_code: String,
+    platform: String,
+    prompt: Prompt,
+    sample: usize,
+    submitted_at: Instant,
+}
+
+/// Run all country/platform lanes of a scan concurrently.
+/// Each lane submits its prompts one after another, while every submitted prompt is
+/// collected and uploaded in its own task as soon as the answer is final. The number of
+/// webviews open at once is capped by the product's `max_concurrent_webviews`.
+async fn run_scan(
+    app: AppHandle,
+    state: Arc<AppState>,
+    checkpoint: ScanCheckpoint,
 ) -> Result<ScanComplete, String> {
-    let mut manager = WebviewManager::new();
+    for platform in &checkpoint.platforms {
+        get_platform_url(platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+    }
+
+    let product_config = storage::get_product_config(&checkpoint.product_id);
+    let max_webviews = product_config.max_concurrent_webviews.max(1) as usize;
+
+    let lanes: Vec<(String, String)> = checkpoint.countries.iter()
+        .flat_map(|country| checkpoint.platforms.iter().map(move |platform| (country.clone(), platform.clone())))
+        .collect();
+
+    let mut open_lanes: HashMap<String, usize> = HashMap::new();
+    for (_, platform) in &lanes {
+        *open_lanes.entry(platform.clone()).or_insert(0) += 1;
+    }
+
+    // Dates come from the session start, so a resumed session sends the same text
+    let started_at = chrono::DateTime::from_timestamp(checkpoint.started_at, 0)
+        .map(|t| t.with_timezone(&chrono::Local))
+        .unwrap_or_else(chrono::Local::now);
+    let prompts: HashMap<String, Vec<Prompt>> = checkpoint.countries.iter()
+        .map(|country_code| {
+            let vars = prompt_template::template_vars(&checkpoint.brand, country_code, started_at);
+            (country_code.clone(), prompt_template::render_prompts(&checkpoint.prompts, country_code, &vars))
+        })
+        .collect();
+
+    let run = Arc::new(ScanRun {
+        app: app.clone(),
+        state: state.clone(),
+        manager: WebviewManager::new(),
+        webview_slots: Arc::new(Semaphore::new(max_webviews)),
+        open_lanes: Mutex::new(open_lanes),
+        limited_lanes: Mutex::new(HashMap::new()),
+        snapshot_mode: product_config.snapshot_mode,
+        upload_snapshots: product_config.upload_snapshots,
+        capture_screenshots: product_config.capture_screenshots,
+        upload_screenshots: product_config.upload_screenshots,
+        render_unsupported: AtomicBool::new(!ANSWER_RENDER_SUPPORTED),
+        scan_session_id: checkpoint.scan_session_id.clone(),
+        product_id: checkpoint.product_id.clone(),
+        prompts,
+        samples: checkpoint.samples,
+        brand: checkpoint.brand_terms(),
+        brand_domain: checkpoint.brand_domain.clone(),
+        domain_aliases: checkpoint.domain_aliases.clone(),
+        competitors: checkpoint.competitors.clone(),
+        checkpoint: Mutex::new(checkpoint),
+        checkpoint_dirty: AtomicBool::new(false),
+    });
 
-    // Update phase
     {
         let mut scan = state.scan.lock();
-        scan.phase = "submitting".to_string();
+        scan.phase = "scanning".to_string();
     }
     emit_progress_with_state(&app, &state);
 
-    let mut total_collected = 0;
-    let mut total_mentioned = 0;
-    let mut total_cited = 0;
+    eprintln!("[Scan] Running {} lanes with up to {} webviews at once", lanes.len(), max_webviews);
 
-    // Process each country
-    for country_code in &scan_countries {
-        let is_local = country_code == "local";
-        eprintln!("[Scan] Processing country: {} (local={})", country_code, is_local);
-
-        // Process each selected platform for this country
-        for platform_str in &selected_platforms {
-            let url = get_platform_url(platform_str)
-                .ok_or_else(|| format!("Unknown platform: {}", platform_str))?;
-            let platform = platform_str.as_str();
-
-            // For geo-targeted scans, check if this country/platform combo is authenticated
-            if !is_local {
-                let is_authenticated = storage::is_country_platform_authenticated(country_code, platform_str);
-                if !is_authenticated {
-                    eprintln!("[Scan] Country {} / Platform {} not authenticated, skipping", country_code, platform_str);
-                    continue;
+    let mut lane_tasks = JoinSet::new();
+    for (country_code, platform) in lanes {
+        lane_tasks.spawn(run_lane(run.clone(), country_code, platform));
+    }
+    // Cells change every few seconds across lanes; write them out in batches instead of
+    // rewriting the whole checkpoint for each one
+    let checkpoint_saver = tokio::spawn({
+        let run = run.clone();
+        async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECKPOINT_SAVE_INTERVAL_SECS)) => {}
+                    // Pausing and resuming are written right away
+                    _ = CHECKPOINT_SAVE_NOW.notified() => {}
                 }
+                run.save_checkpoint();
             }
+        }
+    });
 
-            // Update platform status
-            {
-                let mut scan = state.scan.lock();
-                if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                    ps.status = "submitting".to_string();
-                }
-            }
-            emit_progress_with_state(&app, &state);
+    while let Some(joined) = lane_tasks.join_next().await {
+        if let Err(e) = joined {
+            eprintln!("[Scan] Lane task failed: {}", e);
+        }
+    }
 
-            // Check if user is logged in by creating a test webview
-            // Include scan_session_id and country in label to avoid conflicts
-            let webview_label = format!("scan-{}-{}-{}-check", &scan_session_id[..8], country_code, platform);
+    // Stop the saver before the last write so the two can't write at the same time
+    checkpoint_saver.abort();
+    let _ = checkpoint_saver.await;
+    run.save_checkpoint();
 
-            let login_check = if is_local {
-                manager.create_webview(&app, &webview_label, &url, true).await
-            } else {
-                manager.create_webview_for_country(&app, &webview_label, &url, true, country_code, platform_str).await
-            };
+    if run.is_cancelled() {
+        // Clean up all webviews before returning
+        eprintln!("[Columbus] Scan cancelled - cleaning up all webviews");
+        run.manager.close_all(&app);
+        return Err("Scan cancelled".to_string());
+    }
 
-            if let Err(e) = login_check {
-                // Mark platform as skipped
-                eprintln!("[Scan] Failed to create check webview for {} ({}): {}", platform_str, country_code, e);
-                {
-                    let mut scan = state.scan.lock();
-                    if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                        ps.status = "skipped".to_string();
-                    }
-                }
-                emit_progress_with_state(&app, &state);
-                continue;
-            }
+    {
+        let mut scan = state.scan.lock();
+        scan.phase = "finalizing".to_string();
+    }
+    emit_progress_with_state(&app, &state);
 
-            // Wait for page to load
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    // Finalize scan (queued behind any results still waiting in the outbox)
+    crate::outbox::finalize_or_enqueue(&app, &state, &run.scan_session_id, &run.product_id).await;
 
-            // Check login status
-            let is_logged_in = manager
-                .check_login(&app, &webview_label, platform)
-                .await
-                .unwrap_or(false);
+    // Final cleanup - ensure all webviews are closed
+    eprintln!("[Columbus] Scan complete - performing final webview cleanup");
+    run.manager.close_all(&app);
+    eprintln!("[Columbus] Final webview cleanup complete");
 
-            eprintln!("[Scan] Platform {} ({}) login check result: {}", platform_str, country_code, is_logged_in);
+    // Rates cover the whole session, including cells collected before a resume
+    let checkpoint = run.checkpoint.lock();
+    Ok(summarize_scan(&checkpoint))
+}
 
-            // Close check webview
-            eprintln!("[Columbus] Closing login check webview for {} ({})", platform_str, country_code);
-            manager.close_webview(&app, &webview_label);
+/// Competitors listed per breakdown entry in the scan summary
+const TOP_COMPETITORS: usize = 5;
+
+/// Build the `scan:complete` payload from the checkpoint cells:
+/// overall rates plus a breakdown by platform, by country and by platform × country
+fn summarize_scan(checkpoint: &ScanCheckpoint) -> ScanComplete {
+    let overall = summarize_cells(checkpoint.cells.iter(), None, None);
+
+    let by_platform = checkpoint.platforms.iter()
+        .map(|platform| {
+            let cells = checkpoint.cells.iter().filter(|c| &c.platform == platform);
+            summarize_cells(cells, Some(platform), None)
+        })
+        .collect();
+
+    let by_country = checkpoint.countries.iter()
+        .map(|country| {
+            let cells = checkpoint.cells.iter().filter(|c| &c.country == country);
+            summarize_cells(cells, None, Some(country))
+        })
+        .collect();
+
+    let by_platform_country = checkpoint.platforms.iter()
+        .flat_map(|platform| checkpoint.countries.iter().map(move |country| (platform, country)))
+        .filter(|(platform, country)| checkpoint.cells.iter().any(|c| &c.platform == *platform && &c.country == *country))
+        .map(|(platform, country)| {
+            let cells = checkpoint.cells.iter().filter(|c| &c.platform == platform && &c.country == country);
+            summarize_cells(cells, Some(platform), Some(country))
+        })
+        .collect();
+
+    ScanComplete {
+        total_prompts: checkpoint.cells.len(),
+        successful_prompts: overall.collected,
+        mention_rate: overall.mention_rate,
+        citation_rate: overall.citation_rate,
+        by_platform,
+        by_country,
+        by_platform_country,
+    }
+}
 
-            if !is_logged_in {
-                eprintln!("[Scan] Platform {} ({}) marked as not logged in, skipping", platform_str, country_code);
-                {
-                    let mut scan = state.scan.lock();
-                    if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                        ps.status = "skipped".to_string();
-                    }
+fn summarize_cells<'a>(
+    cells: impl Iterator<Item = &'a ScanCell>,
+    platform: Option<&str>,
+    country: Option<&str>,
+) -> ScanBreakdown {
+    let mut collected = 0;
+    let mut failed = 0;
+    let mut skipped = 0;
+    let mut limited = 0;
+    let mut mentioned = 0;
+    let mut cited = 0;
+    let mut credits_exhausted = 0;
+    let mut competitor_counts: HashMap<&str, usize> = HashMap::new();
+
+    for cell in cells {
+        match cell.status {
+            ScanCellStatus::Collected => {
+                collected += 1;
+                if cell.brand_mentioned == Some(true) {
+                    mentioned += 1;
+                }
+                if cell.citation_present == Some(true) {
+                    cited += 1;
+                }
+                if cell.credits_exhausted == Some(true) {
+                    credits_exhausted += 1;
+                }
+                for competitor in &cell.competitor_mentions {
+                    *competitor_counts.entry(competitor.as_str()).or_default() += 1;
                 }
-                emit_progress_with_state(&app, &state);
-                continue;
             }
+            ScanCellStatus::Failed => failed += 1,
+            ScanCellStatus::Skipped => skipped += 1,
+            ScanCellStatus::Limited => limited += 1,
+            ScanCellStatus::Pending | ScanCellStatus::Submitted => {}
+        }
+    }
 
-            // Process prompts for this platform/country combo
-            // Only process prompts that target this specific country
-            let prompts_for_country: Vec<_> = prompts.iter().enumerate().filter(|(_, p)| {
-                if p.target_regions.is_empty() {
-                    // No regions specified - only run in "local"
-                    is_local
-                } else {
-                    // Check if this country is in the prompt's target regions
-                    p.target_regions.iter().any(|r| r.to_lowercase() == country_code.to_lowercase())
-                }
-            }).collect();
-
-            eprintln!("[Scan] Platform {} ({}) passed login check, processing {} prompts (of {} total) x {} samples",
-                platform_str, country_code, prompts_for_country.len(), prompts.len(), samples);
-
-            for (prompt_idx, prompt) in prompts_for_country {
-                for sample in 0..samples {
-                    // Check if scan was cancelled
-                    {
-                        let scan = state.scan.lock();
-                        if !scan.is_running {
-                            // Clean up all webviews before returning
-                            eprintln!("[Columbus] Scan cancelled - cleaning up all webviews");
-                            manager.close_all(&app);
-                            return Err("Scan cancelled".to_string());
-                        }
-                    }
+    let rate = |count: usize| if collected > 0 { (count as f64 / collected as f64) * 100.0 } else { 0.0 };
+
+    let mut top_competitors: Vec<CompetitorCount> = competitor_counts.into_iter()
+        .map(|(name, mentions)| CompetitorCount { name: name.to_string(), mentions })
+        .collect();
+    top_competitors.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+    top_competitors.truncate(TOP_COMPETITORS);
+
+    ScanBreakdown {
+        platform: platform.map(str::to_string),
+        country: country.map(str::to_string),
+        collected,
+        failed,
+        skipped,
+        limited,
+        mention_rate: rate(mentioned),
+        citation_rate: rate(cited),
+        credits_exhausted,
+        top_competitors,
+    }
+}
 
-                    // Include scan_session_id, country prefix to avoid label conflicts between scans
-                    let webview_label = format!("scan-{}-{}-{}-{}-{}", &scan_session_id[..8], country_code, platform, prompt_idx, sample);
-
-                    // Create webview for this prompt (with or without proxy based on country)
-                    let is_visible = false;
-                    eprintln!("[Columbus] Creating scan webview for platform={}, is_visible={}", platform, is_visible);
-                    let create_result = if is_local {
-                        manager.create_webview(&app, &webview_label, &url, is_visible).await
-                    } else {
-                        manager.create_webview_for_country(&app, &webview_label, &url, is_visible, country_code, platform_str).await
-                    };
-
-                    if let Err(e) = create_result {
-                        eprintln!("Failed to create webview: {}", e);
-                        {
-                            let mut scan = state.scan.lock();
-                            if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                                ps.failed += 1;
-                            }
-                        }
-                        continue;
-                    }
+/// Run one country/platform lane: check login, then submit each pending prompt in its own
+/// webview and hand it off to a collection task.
+async fn run_lane(run: Arc<ScanRun>, country_code: String, platform: String) {
+    let is_local = country_code == "local";
+    let Some(url) = get_platform_url(&platform) else {
+        run.finish_lane(&platform);
+        return;
+    };
 
-                    // Wait for page load
-                    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-
-                    // Submit prompt
-                    let submit_result = manager
-                        .submit_prompt(&app, &webview_label, platform, &prompt.text)
-                        .await;
-
-                    // For google_ai_mode, the first script clicks the AI Mode button which navigates
-                    // to a new page. We need to re-inject the script after navigation completes.
-                    if platform == "google_ai_mode" {
-                        // Wait for navigation to AI Mode page
-                        tokio::time::sleep(tokio::time::Duration::from_secs(4)).await;
-                        // Re-inject the script on the AI Mode page
-                        let _ = manager
-                            .submit_prompt(&app, &webview_label, platform, &prompt.text)
-                            .await;
-                    }
+    // Cells not collected by a previous run of this session (limited cells were re-queued)
+    let pending: Vec<(usize, Prompt, usize)> = {
+        let checkpoint = run.checkpoint.lock();
+        prompts_for_country(run.prompts.get(&country_code).map(Vec::as_slice).unwrap_or_default(), &country_code)
+            .into_iter()
+            .flat_map(|(prompt_idx, prompt)| (0..run.samples).map(move |sample| (prompt_idx, prompt, sample)))
+            .filter(|(_, prompt, sample)| !matches!(
+                checkpoint.cell_status(&country_code, &platform, &prompt.id, *sample),
+                Some(ScanCellStatus::Collected | ScanCellStatus::Limited)
+            ))
+            .map(|(prompt_idx, prompt, sample)| (prompt_idx, prompt.clone(), sample))
+            .collect()
+    };
 
-                    if submit_result.is_ok() {
-                        {
-                            let mut scan = state.scan.lock();
-                            if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                                ps.submitted += 1;
-                            }
-                        }
-                        emit_progress_with_state(&app, &state);
-                    }
-                }
-            }
+    if pending.is_empty() {
+        eprintln!("[Scan] Country {} / Platform {} has nothing left to scan, skipping", country_code, platform);
+        run.finish_lane(&platform);
+        return;
+    }
 
-            // Update to waiting phase
-            {
-                let mut scan = state.scan.lock();
-                if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                    ps.status = "waiting".to_string();
-                }
+    // For geo-targeted scans, check if this country/platform combo is authenticated
+    if !is_local && !storage::is_country_platform_authenticated(&country_code, &platform) {
+        eprintln!("[Scan] Country {} / Platform {} not authenticated, skipping", country_code, platform);
+        run.update_checkpoint(|checkpoint| checkpoint_skip_lane(checkpoint, &country_code, &platform));
+        run.finish_lane(&platform);
+        return;
+    }
+
+    run.update_platform(&platform, |ps| ps.status = "running".to_string());
+
+    // Check if user is logged in by creating a test webview (counts against the webview cap)
+    let is_logged_in = {
+        let Some(_slot) = run.acquire_webview_slot().await else {
+            run.finish_lane(&platform);
+            return;
+        };
+
+        let webview_label = format!("scan-{}-{}-{}-check", &run.scan_session_id[..8], country_code, platform);
+        let login_check = if is_local {
+            run.manager.create_webview(&run.app, &webview_label, &url, true).await
+        } else {
+            run.manager.create_webview_for_country(&run.app, &webview_label, &url, true, &country_code, &platform).await
+        };
+
+        match login_check {
+            Ok(()) => {
+                // Wait for page to load
+                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+                let logged_in = run.manager
+                    .check_login(&run.app, &webview_label, &platform)
+                    .await
+                    .unwrap_or(false);
+
+                eprintln!("[Columbus] Closing login check webview for {} ({})", platform, country_code);
+                run.manager.close_webview(&run.app, &webview_label);
+                logged_in
+            }
+            Err(e) => {
+                eprintln!("[Scan] Failed to create check webview for {} ({}): {}", platform, country_code, e);
+                false
             }
-            emit_progress_with_state(&app, &state);
         }
+    };
+
+    eprintln!("[Scan] Platform {} ({}) login check result: {}", platform, country_code, is_logged_in);
+
+    if !is_logged_in {
+        eprintln!("[Scan] Platform {} ({}) marked as not logged in, skipping", platform, country_code);
+        notifications::notify(
+            &run.app,
+            NotificationKind::LoggedOut,
+            &rate_limit::account_key(&country_code, &platform),
+            &format!("Logged out of {}", notifications::lane_label(&country_code, &platform)),
+            "It was skipped in this scan. Log in again to include it in the next one.",
+        );
+        run.update_checkpoint(|checkpoint| checkpoint_skip_lane(checkpoint, &country_code, &platform));
+        run.finish_lane(&platform);
+        return;
     }
 
-    // Wait for responses with countdown
-    {
-        let mut scan = state.scan.lock();
-        scan.phase = "waiting".to_string();
+    eprintln!("[Scan] Platform {} ({}) passed login check, processing {} prompt samples",
+        platform, country_code, pending.len());
+
+    let mut collect_tasks = JoinSet::new();
+
+    for (prompt_idx, prompt, sample) in pending {
+        // The account hit a usage limit; what's left is re-queued below
+        if run.lane_limit(&country_code, &platform).is_some() {
+            break;
+        }
+
+        // Pace prompts on this account before taking a slot, so a waiting lane doesn't block others
+        if !rate_limit::wait_for_turn(&country_code, &platform, || !run.is_cancelled()).await {
+            break;
+        }
+
+        // Wait for a free webview slot; it stays taken until the response is collected.
+        // Stops here while paused; prompts already submitted are still collected meanwhile
+        let Some(slot) = run.acquire_webview_slot().await else {
+            break;
+        };
+
+        let webview_label = run.webview_label(&country_code, &platform, prompt_idx, sample);
+
+        // Create webview for this prompt (with or without proxy based on country)
+        let is_visible = false;
+        eprintln!("[Columbus] Creating scan webview for platform={}, is_visible={}", platform, is_visible);
+        let create_result = if is_local {
+            run.manager.create_webview(&run.app, &webview_label, &url, is_visible).await
+        } else {
+            run.manager.create_webview_for_country(&run.app, &webview_label, &url, is_visible, &country_code, &platform).await
+        };
+
+        if let Err(e) = create_result {
+            eprintln!("Failed to create webview: {}", e);
+            run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Failed));
+            run.update_platform(&platform, |ps| ps.failed += 1);
+            continue;
+        }
+
+        // Wait for page load
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+        // Claim the account's turn right before sending; a follow-up may have taken it meanwhile
+        if !rate_limit::reserve_turn(&country_code, &platform, || !run.is_cancelled()).await {
+            run.manager.close_webview(&run.app, &webview_label);
+            break;
+        }
+
+        // Submit prompt
+        let submit_result = run.manager
+            .submit_prompt(&run.app, &webview_label, &platform, &prompt.text)
+            .await;
+
+        // For google_ai_mode, the first script clicks the AI Mode button which navigates
+        // to a new page. We need to re-inject the script after navigation completes.
+        if platform == "google_ai_mode" {
+            // Wait for navigation to AI Mode page
+            tokio::time::sleep(tokio::time::Duration::from_secs(4)).await;
+            // Re-inject the script on the AI Mode page
+            let _ = run.manager
+                .submit_prompt(&run.app, &webview_label, &platform, &prompt.text)
+                .await;
+        }
+
+        if let Err(e) = submit_result {
+            eprintln!("Failed to submit prompt in {}: {}", webview_label, e);
+            run.manager.close_webview(&run.app, &webview_label);
+            run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Failed));
+            run.update_platform(&platform, |ps| ps.failed += 1);
+            continue;
+        }
+
+        // Limit notices replace the answer right away; catch them before submitting more
+        tokio::time::sleep(tokio::time::Duration::from_secs(LIMIT_CHECK_DELAY_SECS)).await;
+        if let Ok(Some(notice)) = run.manager.check_usage_limit(&run.app, &webview_label, &platform, &prompt.text).await {
+            run.manager.close_webview(&run.app, &webview_label);
+            run.mark_lane_limited(&country_code, &platform, &notice);
+            // The cell is re-queued with the rest of the lane
+            run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Pending));
+            break;
+        }
+
+        run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Submitted));
+        run.update_platform(&platform, |ps| ps.submitted += 1);
+
+        let submitted = SubmittedCell {
+            label: webview_label,
+            country_code: country_code.clone(),
+            platform: platform.clone(),
+            prompt,
+            sample,
+            submitted_at: Instant::now(),
+        };
+        collect_tasks.spawn(collect_cell(run.clone(), slot, submitted));
     }
 
-    // Countdown from 45 seconds, emitting progress every second
-    const WAIT_SECONDS: usize = 45;
-    for remaining in (0..=WAIT_SECONDS).rev() {
-        emit_progress_with_countdown(&app, &state, remaining);
-        if remaining > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    while let Some(joined) = collect_tasks.join_next().await {
+        if let Err(e) = joined {
+            eprintln!("[Scan] Collection task failed: {}", e);
         }
     }
 
-    // Collection phase
-    {
-        let mut scan = state.scan.lock();
-        scan.phase = "collecting".to_string();
+    if let Some(resets_at) = run.lane_limit(&country_code, &platform) {
+        requeue_limited_lane(&run, &country_code, &platform, resets_at);
     }
-    emit_progress_with_state(&app, &state);
 
-    // Collect responses from all webviews (iterate through countries and platforms)
-    for country_code in &scan_countries {
-        let is_local = country_code == "local";
+    run.finish_lane(&platform);
+}
 
-        for platform_str in &selected_platforms {
-            let platform = platform_str.as_str();
+/// Queue the prompts a usage limit kept a lane from running, to start after the limit resets
+/// (or after `LIMIT_FALLBACK_DEFER_SECS` if the platform didn't say when). After
+/// `MAX_UNTIMED_LIMIT_REQUEUES` re-queues in a row without a reset time the prompts are
+/// marked failed instead, so an account that never recovers doesn't loop forever.
+fn requeue_limited_lane(run: &ScanRun, country_code: &str, platform: &str, resets_at: Option<i64>) {
+    let limit_requeues = match resets_at {
+        Some(_) => 0,
+        None => scan_queue::current_job().map(|job| job.limit_requeues).unwrap_or(0) + 1,
+    };
+    if limit_requeues > MAX_UNTIMED_LIMIT_REQUEUES {
+        eprintln!("[Scan] {} ({}) is still limited after {} re-queues; marking its prompts failed",
+            platform, country_code, MAX_UNTIMED_LIMIT_REQUEUES);
+        run.update_checkpoint(|checkpoint| checkpoint_fail_lane(checkpoint, country_code, platform));
+        return;
+    }
+
+    let prompt_ids = run.update_checkpoint(|checkpoint| checkpoint_limit_lane(checkpoint, country_code, platform));
+    if prompt_ids.is_empty() {
+        return;
+    }
 
-            // Skip if not authenticated for this country/platform combo (non-local)
-            if !is_local && !storage::is_country_platform_authenticated(country_code, platform_str) {
-                continue;
+    let not_before = resets_at.unwrap_or_else(|| chrono::Utc::now().timestamp() + LIMIT_FALLBACK_DEFER_SECS);
+    eprintln!("[Scan] Re-queueing {} prompts for {} ({}) after {}", prompt_ids.len(), platform, country_code, not_before);
+
+    if let Err(e) = scan_queue::enqueue_deferred(
+        &run.app,
+        run.product_id.clone(),
+        platform.to_string(),
+        country_code.to_string(),
+        prompt_ids,
+        run.samples,
+        not_before,
+        limit_requeues,
+    ) {
+        eprintln!("[Scan] Failed to re-queue limited prompts for {} ({}): {}", platform, country_code, e);
+    }
+}
+
+/// Wait for a submitted prompt's answer, collect it, free its webview slot and upload the result
+async fn collect_cell(run: Arc<ScanRun>, slot: OwnedSemaphorePermit, cell: SubmittedCell) {
+    let SubmittedCell { label, country_code, platform, prompt, sample, submitted_at } = cell;
+
+    // Wait until the platform has finished answering (or its maximum wait runs out)
+    let completion = run.manager
+        .wait_for_completion(&run.app, &label, &platform, submitted_at, None, |_| !run.is_cancelled())
+        .await;
+
+    if run.is_cancelled() {
+        // Leave the cell as submitted; resuming the session will run it again
+        run.manager.close_webview(&run.app, &label);
+        return;
+    }
+
+    let (response_wait_ms, response_timed_out, answer_hash) = match completion {
+        Ok(outcome) => (Some(outcome.waited_ms), !outcome.completed, Some(outcome.answer_hash)),
+        Err(e) => {
+            eprintln!("[Scan] Completion detection failed for {}: {}", label, e);
+            (None, false, None)
+        }
+    };
+
+    // Collect response
+    let collect_result = run.manager
+        .collect_response(
+            &run.app,
+            &label,
+            &platform,
+            &run.brand,
+            run.brand_domain.as_deref(),
+            run.domain_aliases.as_deref(),
+            &run.competitors,
+        )
+        .await;
+
+    // Continue the conversation in the same chat
+    let turns = match &collect_result {
+        Ok(first) if !prompt.follow_ups.is_empty() => {
+            let first_turn = conversation_turn(&prompt.text, first, &[], response_wait_ms, response_timed_out);
+            collect_follow_ups(&run, &label, &country_code, &platform, &prompt, first_turn, answer_hash).await
+        }
+        _ => Vec::new(),
+    };
+
+    // A conversation cut short runs again from the start: on resume if the scan was cancelled,
+    // with the lane's re-queued prompts if the account hit a usage limit
+    let cut_short = !turns.is_empty() && supports_follow_ups(&platform) && turns.len() <= prompt.follow_ups.len();
+    if run.is_cancelled() || (cut_short && run.lane_limit(&country_code, &platform).is_some()) {
+        run.manager.close_webview(&run.app, &label);
+        if !run.is_cancelled() {
+            run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Pending));
+        }
+        return;
+    }
+
+    // Archive the page as the platform showed it, before the webview goes away
+    let snapshot = match run.snapshot_mode {
+        SnapshotMode::Off => None,
+        mode => match run.manager.capture_snapshot(&run.app, &label, &platform, mode == SnapshotMode::FullPage).await {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                eprintln!("[Snapshot] Failed to capture {}: {}", label, e);
+                None
             }
+        },
+    };
 
-            {
-                let mut scan = state.scan.lock();
-                if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                    if ps.status == "skipped" {
-                        continue;
-                    }
-                    ps.status = "collecting".to_string();
-                }
+    // An out-of-credits answer means the account is limited; stop the lane from submitting more
+    if collect_result.as_ref().is_ok_and(|r| r.credits_exhausted) {
+        let notice = run.manager
+            .check_usage_limit(&run.app, &label, &platform, &prompt.text)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        run.mark_lane_limited(&country_code, &platform, &notice);
+    }
+
+    // Image evidence of the answer: a DOM render drawn in the page, so hidden webviews work
+    // too. Best effort and Windows-only; elsewhere the scan goes on without images
+    let screenshot = if run.capture_screenshots && collect_result.is_ok() && !run.render_unsupported.load(Ordering::Relaxed) {
+        match run.manager.render_answer_image(&run.app, &label, &platform).await {
+            Ok(png) => Some(png),
+            Err(AnswerRenderError::Unsupported) => {
+                eprintln!("[Screenshot] This webview can't export answer renders; skipping them for the rest of the scan");
+                run.render_unsupported.store(true, Ordering::Relaxed);
+                None
+            }
+            Err(e) => {
+                eprintln!("[Screenshot] Failed to render {}: {}", label, e);
+                None
             }
-            emit_progress_with_state(&app, &state);
+        }
+    } else {
+        None
+    };
 
-            // Only collect prompts that target this specific country (same filter as submission)
-            let prompts_for_country: Vec<_> = prompts.iter().enumerate().filter(|(_, p)| {
-                if p.target_regions.is_empty() {
-                    is_local
-                } else {
-                    p.target_regions.iter().any(|r| r.to_lowercase() == country_code.to_lowercase())
-                }
-            }).collect();
-
-            for (prompt_idx, prompt) in prompts_for_country {
-                for sample in 0..samples {
-                    // Use same label format as submission phase (includes country)
-                    let webview_label = format!("scan-{}-{}-{}-{}-{}", &scan_session_id[..8], country_code, platform, prompt_idx, sample);
-
-                // Collect response
-                let collect_result = manager
-                    .collect_response(&app, &webview_label, platform, &brand, brand_domain.as_deref(), domain_aliases.as_deref(), &competitors)
-                    .await;
-
-                match collect_result {
-                    Ok(response) => {
-                        total_collected += 1;
-                        if response.brand_mentioned {
-                            total_mentioned += 1;
-                        }
-                        if response.citation_present {
-                            total_cited += 1;
-                        }
-
-                        // Submit result to API
-                        let scan_result = ScanResult {
-                            product_id: product_id.clone(),
-                            scan_session_id: scan_session_id.clone(),
-                            platform: platform_str.clone(),
-                            prompt_id: prompt.id.clone(),
-                            prompt_text: prompt.text.clone(),
-                            response_text: response.response_text,
-                            brand_mentioned: response.brand_mentioned,
-                            citation_present: response.citation_present,
-                            position: response.position,
-                            sentiment: response.sentiment.clone(),
-                            competitor_mentions: response.competitor_mentions,
-                            competitor_details: response.competitor_details.iter().map(|cd| {
-                                crate::CompetitorDetailResult {
-                                    name: cd.name.clone(),
-                                    position: cd.position,
-                                    sentiment: cd.sentiment.clone(),
-                                }
-                            }).collect(),
-                            citations: response.citations,
-                            credits_exhausted: response.credits_exhausted,
-                            chat_url: response.chat_url,
-                            request_country: Some(country_code.clone()),
-                        };
-
-                        // Submit to API with logging - ensure token is still valid
-                        let token = match crate::commands::auth::ensure_valid_token(&state).await {
-                            Ok(t) => Some(t),
-                            Err(_) => {
-                                let auth = state.auth.lock();
-                                auth.access_token.clone()
-                            }
-                        };
-
-                        if let Some(token) = token {
-                            let client = reqwest::Client::new();
-                            eprintln!("Submitting scan result for {} prompt {} to API...", platform_str, prompt.id);
-
-                            match client
-                                .post(format!(
-                                    "{}/functions/v1/extension-scan-results",
-                                    crate::SUPABASE_URL
-                                ))
-                                .header("Authorization", format!("Bearer {}", token))
-                                .header("apikey", crate::SUPABASE_ANON_KEY)
-                                .header("Content-Type", "application/json")
-                                .json(&scan_result)
-                                .send()
-                                .await
-                            {
-                                Ok(response) => {
-                                    let status = response.status();
-                                    if status.is_success() {
-                                        eprintln!("API submission successful: {}", status);
-                                    } else {
-                                        let body = response.text().await.unwrap_or_default();
-                                        eprintln!("API submission failed: {} - {}", status, body);
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("API request error: {}", e);
-                                }
-                            }
-                        } else {
-                            eprintln!("No auth token available for API submission");
-                        }
-
-                        {
-                            let mut scan = state.scan.lock();
-                            if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                                ps.collected += 1;
-                            }
-                            scan.completed_prompts += 1;
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to collect response: {}", e);
-                        {
-                            let mut scan = state.scan.lock();
-                            if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                                ps.failed += 1;
-                            }
-                        }
-                    }
-                }
+    // Close webview and free its slot before uploading so the next prompt can start
+    eprintln!("[Columbus] Closing scan webview after response collection: {}", label);
+    run.manager.close_webview(&run.app, &label);
+    drop(slot);
 
-                emit_progress_with_state(&app, &state);
+    // Snapshots are kept even when collection failed; that's when they're most useful
+    let snapshot_path = snapshot.and_then(|bytes| {
+        storage::save_snapshot(&run.scan_session_id, &platform, &country_code, &prompt.id, sample, &bytes)
+            .map_err(|e| eprintln!("[Snapshot] Failed to save {}: {}", label, e))
+            .ok()
+    });
+    if let (Some(path), true) = (&snapshot_path, run.upload_snapshots) {
+        if let Err(e) = upload_evidence_file(&run.state, &run.product_id, &run.scan_session_id, path).await {
+            eprintln!("[Snapshot] Failed to upload {:?}: {}", path, e);
+        }
+    }
 
-                // Close webview after collecting response
-                eprintln!("[Columbus] Closing scan webview after response collection: {}", webview_label);
-                manager.close_webview(&app, &webview_label);
-            }
+    let screenshot_path = screenshot.and_then(|png| {
+        storage::save_screenshot(&run.scan_session_id, &platform, &country_code, &prompt.id, sample, &png)
+            .map_err(|e| eprintln!("[Screenshot] Failed to save {}: {}", label, e))
+            .ok()
+    });
+    let mut screenshot_object_path = None;
+    if let (Some(path), true) = (&screenshot_path, run.upload_screenshots) {
+        match upload_evidence_file(&run.state, &run.product_id, &run.scan_session_id, path).await {
+            Ok(object_path) => screenshot_object_path = Some(object_path),
+            Err(e) => eprintln!("[Screenshot] Failed to upload {:?}: {}", path, e),
         }
+    }
 
-            // Mark platform complete
-            {
-                let mut scan = state.scan.lock();
-                if let Some(ps) = scan.platforms.get_mut(platform_str) {
-                    ps.status = "complete".to_string();
+    match collect_result {
+        Ok(response) => {
+            let mut scan_result = ScanResult {
+                product_id: run.product_id.clone(),
+                scan_session_id: run.scan_session_id.clone(),
+                platform: platform.clone(),
+                prompt_id: prompt.id.clone(),
+                prompt_text: prompt.text.clone(),
+                response_text: response.response_text,
+                brand_mentioned: response.brand_mentioned,
+                brand_spans: response.brand_spans,
+                citation_present: response.citation_present,
+                position: response.position,
+                sentiment: response.sentiment.clone(),
+                sentiment_score: response.sentiment_score,
+                competitor_mentions: response.competitor_mentions,
+                competitor_details: response.competitor_details,
+                citations: response.citations,
+                credits_exhausted: response.credits_exhausted,
+                chat_url: response.chat_url,
+                request_country: Some(country_code.clone()),
+                response_wait_ms,
+                response_timed_out,
+                screenshot_object_path,
+                turns: Vec::new(),
+                prompt_variant: prompt.variant.clone(),
+                result_key: Some(crate::result_key(&run.scan_session_id, &country_code, &platform, &prompt.id, sample)),
+            };
+            merge_turns(&mut scan_result, turns);
+
+            // Keep a local copy for offline history, independent of the upload
+            let collected_at = chrono::Utc::now().timestamp_millis();
+            let submitted_at_ms = collected_at - submitted_at.elapsed().as_millis() as i64;
+            let snapshot_path = snapshot_path.as_ref().map(|p| p.to_string_lossy().to_string());
+            let screenshot_path = screenshot_path.as_ref().map(|p| p.to_string_lossy().to_string());
+            if let Err(e) = crate::scan_history::record_result(
+                &scan_result,
+                sample,
+                submitted_at_ms,
+                collected_at,
+                snapshot_path.as_deref(),
+                screenshot_path.as_deref(),
+            ) {
+                eprintln!("[History] Failed to store result: {}", e);
+            }
+
+            // Submit to API - results that are not accepted go to the offline outbox
+            crate::outbox::submit_or_enqueue(&run.app, &run.state, &scan_result).await;
+
+            run.update_checkpoint(|checkpoint| {
+                if let Some(cell) = checkpoint.cell_mut(&country_code, &platform, &prompt.id, sample) {
+                    cell.brand_mentioned = Some(scan_result.brand_mentioned);
+                    cell.citation_present = Some(scan_result.citation_present);
+                    cell.credits_exhausted = Some(scan_result.credits_exhausted);
+                    cell.competitor_mentions = scan_result.competitor_mentions.clone();
+                    cell.response_timed_out = Some(scan_result.response_timed_out);
                 }
+                checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Collected);
+            });
+
+            // Webhooks hear about every quarter of the cells collected
+            let quarter_reached = {
+                let mut scan = run.state.scan.lock();
+                scan.completed_prompts += 1;
+                let total = scan.total_prompts.max(1);
+                let quarter = scan.completed_prompts * 4 / total;
+                (quarter > (scan.completed_prompts - 1) * 4 / total && quarter < 4).then_some(quarter * 25)
+            };
+            run.update_platform(&platform, |ps| ps.collected += 1);
+            if let Some(percent) = quarter_reached {
+                notify_progress_milestone(&run.state, &format!("{}_percent", percent), None);
             }
-            emit_progress_with_state(&app, &state);
+        }
+        Err(e) => {
+            eprintln!("Failed to collect response: {}", e);
+            run.update_checkpoint(|checkpoint| checkpoint_cell(checkpoint, &country_code, &platform, &prompt.id, sample, ScanCellStatus::Failed));
+            run.update_platform(&platform, |ps| ps.failed += 1);
         }
     }
+}
 
-    // Finalize scan - refresh token if needed since scan might have taken a while
-    {
-        let token = match crate::commands::auth::ensure_valid_token(&state).await {
-            Ok(t) => Some(t),
+/// Send a prompt's follow-ups into the chat of its first answer, each once the answer before
+/// it is complete, and return every turn, the first one included. Stops early if the scan
+/// is cancelled, the account hits a usage limit or a follow-up can't be submitted.
+async fn collect_follow_ups(
+    run: &ScanRun,
+    label: &str,
+    country_code: &str,
+    platform: &str,
+    prompt: &Prompt,
+    first_turn: ConversationTurn,
+    mut answer_hash: Option<String>,
+) -> Vec<ConversationTurn> {
+    let mut turns = vec![first_turn];
+    if !supports_follow_ups(platform) {
+        eprintln!("[Scan] {} has no chat to continue, ignoring {} follow-ups of prompt {}",
+            platform, prompt.follow_ups.len(), prompt.id);
+        return turns;
+    }
+
+    for follow_up in &prompt.follow_ups {
+        if run.is_cancelled() || run.lane_limit(country_code, platform).is_some() {
+            break;
+        }
+        // Follow-ups are prompts on the account like any other
+        if !rate_limit::reserve_turn(country_code, platform, || !run.is_cancelled()).await {
+            break;
+        }
+
+        if let Err(e) = run.manager.submit_prompt(&run.app, label, platform, follow_up).await {
+            eprintln!("[Scan] Failed to submit follow-up in {}: {}", label, e);
+            break;
+        }
+        let submitted_at = Instant::now();
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(LIMIT_CHECK_DELAY_SECS)).await;
+        if let Ok(Some(notice)) = run.manager.check_usage_limit(&run.app, label, platform, follow_up).await {
+            run.mark_lane_limited(country_code, platform, &notice);
+            break;
+        }
+
+        let completion = run.manager
+            .wait_for_completion(&run.app, label, platform, submitted_at, answer_hash.as_deref(), |_| !run.is_cancelled())
+            .await;
+        if run.is_cancelled() {
+            break;
+        }
+        let (response_wait_ms, response_timed_out) = match completion {
+            Ok(outcome) => {
+                answer_hash = Some(outcome.answer_hash);
+                (Some(outcome.waited_ms), !outcome.completed)
+            }
             Err(e) => {
-                eprintln!("[Scan] Token refresh failed before finalize: {}", e);
-                None
+                eprintln!("[Scan] Completion detection failed for follow-up in {}: {}", label, e);
+                (None, false)
             }
         };
 
-        if let Some(token) = token {
-            let client = reqwest::Client::new();
-            eprintln!("Finalizing scan session {}...", scan_session_id);
-
-            match client
-                .post(format!(
-                    "{}/functions/v1/extension-finalize-scan",
-                    crate::SUPABASE_URL
-                ))
-                .header("Authorization", format!("Bearer {}", token))
-                .header("apikey", crate::SUPABASE_ANON_KEY)
-                .header("Content-Type", "application/json")
-                .json(&serde_json::json!({
-                    "scanSessionId": scan_session_id,
-                    "productId": product_id
-                }))
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    let status = response.status();
-                    if status.is_success() {
-                        eprintln!("Finalize scan successful: {}", status);
-                    } else {
-                        let body = response.text().await.unwrap_or_default();
-                        eprintln!("Finalize scan failed: {} - {}", status, body);
+        let response = run.manager
+            .collect_response(
+                &run.app,
+                label,
+                platform,
+                &run.brand,
+                run.brand_domain.as_deref(),
+                run.domain_aliases.as_deref(),
+                &run.competitors,
+            )
+            .await
+            .unwrap_or_default();
+        let turn = conversation_turn(follow_up, &response, &turns, response_wait_ms, response_timed_out);
+        turns.push(turn);
+        eprintln!("[Scan] {}: turn {} of {} collected", label, turns.len(), prompt.follow_ups.len() + 1);
+    }
+
+    turns
+}
+
+/// One turn of a conversation. The collect script sees the whole chat, so citations that
+/// already came with an earlier answer are left out.
+fn conversation_turn(
+    prompt_text: &str,
+    response: &CollectResponse,
+    earlier: &[ConversationTurn],
+    response_wait_ms: Option<u64>,
+    response_timed_out: bool,
+) -> ConversationTurn {
+    let citations = response.citations.iter()
+        .filter(|c| !earlier.iter().flat_map(|t| &t.citations).any(|e| e.url == c.url))
+        .enumerate()
+        .map(|(i, c)| Citation { position: i as i32 + 1, ..c.clone() })
+        .collect();
+
+    ConversationTurn {
+        prompt_text: prompt_text.to_string(),
+        response_text: response.response_text.clone(),
+        brand_mentioned: response.brand_mentioned,
+        brand_spans: response.brand_spans.clone(),
+        position: response.position,
+        sentiment_score: response.sentiment_score,
+        citation_present: response.citation_present,
+        competitor_mentions: response.competitor_mentions.clone(),
+        competitor_details: response.competitor_details.clone(),
+        citations,
+        response_wait_ms,
+        response_timed_out,
+    }
+}
+
+/// Attach a conversation's turns to its result and widen the mention and citation fields
+/// to cover every turn. Does nothing for single-message prompts (no turns).
+pub fn merge_turns(result: &mut ScanResult, turns: Vec<ConversationTurn>) {
+    if turns.is_empty() {
+        return;
+    }
+
+    result.brand_mentioned = turns.iter().any(|t| t.brand_mentioned);
+    // Ranked in the first answer that mentions the brand, rated across all that do
+    result.position = turns.iter().find_map(|t| t.position);
+    result.sentiment_score = mean_score(turns.iter().map(|t| t.sentiment_score));
+    result.sentiment = sentiment::label(result.sentiment_score).to_string();
+    result.citation_present = turns.iter().any(|t| t.citation_present);
+
+    result.competitor_mentions.clear();
+    for name in turns.iter().flat_map(|t| &t.competitor_mentions) {
+        if !result.competitor_mentions.contains(name) {
+            result.competitor_mentions.push(name.clone());
+        }
+    }
+    // Keep details already assigned to a competitor, add neutral ones for later mentions.
+    // Spans stay those in the first answer; mention counts, matched aliases and sentiment
+    // cover all turns, and the rank is from the first answer that mentions the competitor.
+    let details = std::mem::take(&mut result.competitor_details);
+    result.competitor_details = result.competitor_mentions.iter()
+        .map(|name| {
+            let mut detail = details.iter()
+                .find(|cd| &cd.name == name)
+                .cloned()
+                .unwrap_or_else(|| CompetitorDetailResult::unrated(name));
+            let per_turn: Vec<&CompetitorDetailResult> = turns.iter()
+                .flat_map(|t| &t.competitor_details)
+                .filter(|cd| &cd.name == name)
+                .collect();
+            if !per_turn.is_empty() {
+                detail.position = per_turn.iter().find_map(|cd| cd.position);
+                detail.sentiment_score = mean_score(per_turn.iter().map(|cd| cd.sentiment_score));
+                detail.sentiment = sentiment::label(detail.sentiment_score).to_string();
+                detail.mentions = per_turn.iter().map(|cd| cd.mentions).sum();
+                for alias in per_turn.iter().flat_map(|cd| &cd.matched_aliases) {
+                    if !detail.matched_aliases.contains(alias) {
+                        detail.matched_aliases.push(alias.clone());
                     }
                 }
-                Err(e) => {
-                    eprintln!("Finalize scan request error: {}", e);
+            }
+            detail
+        })
+        .collect();
+
+    result.citations = turns.iter()
+        .flat_map(|t| &t.citations)
+        .enumerate()
+        .map(|(i, c)| Citation { position: i as i32 + 1, ..c.clone() })
+        .collect();
+    result.turns = turns;
+}
+
+/// Average of the scores that are set, None if none is
+fn mean_score(scores: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    let scores: Vec<f64> = scores.flatten().collect();
+    if scores.is_empty() {
+        return None;
+    }
+    let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+    Some((mean * 1000.0).round() / 1000.0)
+}
+
+/// Seconds between writes of a running scan's checkpoint
+const CHECKPOINT_SAVE_INTERVAL_SECS: u64 = 5;
+
+lazy_static::lazy_static! {
+    /// Has the running scan write its checkpoint without waiting for the interval
+    static ref CHECKPOINT_SAVE_NOW: tokio::sync::Notify = tokio::sync::Notify::new();
+}
+
+/// Seconds after submission before checking the page for a usage-limit notice
+const LIMIT_CHECK_DELAY_SECS: u64 = 2;
+/// How long prompts cut off by a limit wait when the platform doesn't show a reset time
+const LIMIT_FALLBACK_DEFER_SECS: i64 = 60 * 60;
+/// Re-queues in a row a lane gets when the platform shows no reset time, before giving up
+const MAX_UNTIMED_LIMIT_REQUEUES: u32 = 3;
+
+/// Seconds a cell spends before its answer starts: page load plus prompt submission
+const CELL_SUBMIT_SECS: u64 = 5;
+/// Seconds a lane spends on its login check webview
+const LANE_LOGIN_CHECK_SECS: u64 = 5;
+
+/// Dry-run view of what `start_scan` would execute
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanPlan {
+    pub product_id: String,
+    pub platforms: Vec<String>,
+    pub samples: usize,
+    pub countries: Vec<CountryPlan>,
+    /// Countries other than "local" that have no static proxy and would run from this machine's IP
+    pub countries_without_proxy: Vec<String>,
+    /// All cells in the matrix (prompts × samples × platforms, per country)
+    pub total_cells: usize,
+    /// Cells in lanes that will not be skipped
+    pub runnable_cells: usize,
+    pub max_concurrent_webviews: usize,
+    pub estimated_duration_secs: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryPlan {
+    pub country: String,
+    pub prompt_ids: Vec<String>,
+    pub has_proxy: bool,
+    pub lanes: Vec<LanePlan>,
+}
+
+/// One country/platform pair of the plan
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanePlan {
+    pub platform: String,
+    pub cells: usize,
+    /// Why the lane would be skipped ("unknown_platform", "not_authenticated"), None if it runs.
+    /// Login is only verified when the scan actually runs.
+    pub skip_reason: Option<String>,
+}
+
+/// Build the execution matrix for a scan without running it
+#[tauri::command]
+pub async fn plan_scan(
+    product_id: String,
+    samples_per_prompt: Option<usize>,
+    platforms: Option<Vec<String>>,
+    prompt_filter: Option<PromptFilter>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ScanPlan, String> {
+    let selected_platforms = platforms.unwrap_or_else(default_scan_platforms);
+    let samples = samples_per_prompt.unwrap_or(1);
+    let prompt_filter = prompt_filter.unwrap_or_default();
+
+    let prompts_response = fetch_scan_prompts(state.inner(), &product_id, &prompt_filter).await?;
+    let prompts = prompts_response.prompts;
+
+    let max_concurrent_webviews = storage::get_product_config(&product_id)
+        .max_concurrent_webviews
+        .max(1) as usize;
+
+    let mut countries = Vec::new();
+    let mut countries_without_proxy = Vec::new();
+    let mut total_cells = 0;
+    let mut runnable_cells = 0;
+    // Estimated seconds of webview work in total, and the longest single lane
+    let mut total_work_secs = 0;
+    let mut longest_lane_secs = 0;
+    let mut runnable_lanes = 0;
+
+    for country_code in scan_countries_for_prompts(&prompts) {
+        let is_local = country_code == "local";
+        let country_prompts = prompts_for_country(&prompts, &country_code);
+        let has_proxy = is_local || storage::get_static_proxy(&country_code).is_some();
+        if !has_proxy {
+            countries_without_proxy.push(country_code.clone());
+        }
+
+        let mut lanes = Vec::new();
+        for platform in &selected_platforms {
+            let cells = country_prompts.len() * samples;
+            total_cells += cells;
+
+            let skip_reason = if get_platform_url(platform).is_none() {
+                Some("unknown_platform".to_string())
+            } else if !is_local && !storage::is_country_platform_authenticated(&country_code, platform) {
+                Some("not_authenticated".to_string())
+            } else {
+                None
+            };
+
+            if skip_reason.is_none() && cells > 0 {
+                runnable_cells += cells;
+                runnable_lanes += 1;
+
+                let mut submit_secs = CELL_SUBMIT_SECS;
+                if platform == "google_ai_mode" {
+                    // AI Mode navigates once more before the prompt can be submitted
+                    submit_secs += 4;
+                }
+                // Prompts on one account are spaced by the platform's rate limit (average jitter)
+                let policy = storage::get_rate_limit(platform);
+                let prompt_spacing_secs = submit_secs.max(policy.min_gap_secs + policy.jitter_secs / 2);
+                // Assume a typical answer takes about half of the platform's maximum wait
+                let answer_secs = completion_timeout_secs(platform) / 2;
+
+                // Follow-ups keep a cell's webview open for another spaced prompt and answer each
+                let follow_ups = if supports_follow_ups(platform) {
+                    country_prompts.iter().map(|(_, p)| p.follow_ups.len()).sum::<usize>() * samples
+                } else {
+                    0
+                };
+                let follow_up_secs = follow_ups as u64 * (prompt_spacing_secs + answer_secs);
+
+                total_work_secs += LANE_LOGIN_CHECK_SECS + cells as u64 * (submit_secs + answer_secs) + follow_up_secs;
+                // A lane submits its prompts one after another
+                let mut lane_secs = LANE_LOGIN_CHECK_SECS + cells as u64 * prompt_spacing_secs + answer_secs + follow_up_secs;
+                if policy.max_per_hour > 0 {
+                    // Every full hourly batch beyond the first waits for the window to roll over
+                    lane_secs = lane_secs.max(((cells + follow_ups) as u64 - 1) / policy.max_per_hour as u64 * 3600 + answer_secs);
                 }
+                longest_lane_secs = longest_lane_secs.max(lane_secs);
             }
-        } else {
-            eprintln!("No auth token available for finalize scan");
+
+            lanes.push(LanePlan {
+                platform: platform.clone(),
+                cells,
+                skip_reason,
+            });
         }
+
+        countries.push(CountryPlan {
+            country: country_code,
+            prompt_ids: country_prompts.iter().map(|(_, p)| p.id.clone()).collect(),
+            has_proxy,
+            lanes,
+        });
     }
 
-    // Final cleanup - ensure all webviews are closed
-    eprintln!("[Columbus] Scan complete - performing final webview cleanup");
-    manager.close_all(&app);
-    eprintln!("[Columbus] Final webview cleanup complete");
+    let parallelism = max_concurrent_webviews.min(runnable_lanes.max(1)) as u64;
+    let estimated_duration_secs = (total_work_secs / parallelism).max(longest_lane_secs);
+
+    Ok(ScanPlan {
+        product_id,
+        platforms: selected_platforms,
+        samples,
+        countries,
+        countries_without_proxy,
+        total_cells,
+        runnable_cells,
+        max_concurrent_webviews,
+        estimated_duration_secs,
+    })
+}
 
-    let mention_rate = if total_collected > 0 {
-        (total_mentioned as f64 / total_collected as f64) * 100.0
-    } else {
-        0.0
-    };
+/// Scan session that was interrupted (crash, quit, cancel or error) and can be resumed
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableScan {
+    pub scan_session_id: String,
+    pub product_id: String,
+    pub status: String,
+    pub platforms: Vec<String>,
+    pub total_cells: usize,
+    pub collected_cells: usize,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
 
-    let citation_rate = if total_collected > 0 {
-        (total_cited as f64 / total_collected as f64) * 100.0
-    } else {
-        0.0
+/// List scan sessions that did not finish and can be picked up with `resume_scan`
+#[tauri::command]
+pub async fn get_resumable_scans(state: State<'_, Arc<AppState>>) -> Result<Vec<ResumableScan>, String> {
+    // The session that is running right now is not resumable
+    let running_session = {
+        let scan = state.scan.lock();
+        if scan.is_running { scan.scan_session_id.clone() } else { None }
     };
 
-    // Calculate total prompt executions for the completion stats
-    let mut completion_total: usize = 0;
-    for prompt in &prompts {
-        if prompt.target_regions.is_empty() {
-            completion_total += 1;
+    Ok(storage::list_scan_checkpoints()
+        .into_iter()
+        .filter(|c| c.status != "complete" && Some(&c.scan_session_id) != running_session.as_ref())
+        .map(|c| ResumableScan {
+            collected_cells: c.cells.iter().filter(|cell| cell.status == ScanCellStatus::Collected).count(),
+            total_cells: c.cells.len(),
+            scan_session_id: c.scan_session_id,
+            product_id: c.product_id,
+            status: c.status,
+            platforms: c.platforms,
+            started_at: c.started_at,
+            updated_at: c.updated_at,
+        })
+        .collect())
+}
+
+/// Resume a paused scan, or an interrupted scan session under the same scan_session_id.
+/// For interrupted sessions, cells that were already collected are skipped; everything
+/// else runs again. Without an id, the paused scan or else the most recently updated
+/// unfinished session is resumed.
+#[tauri::command]
+pub async fn resume_scan(
+    scan_session_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    // A paused scan in this process simply continues where it stopped
+    let unpaused = {
+        let mut scan = state.scan.lock();
+        let matches_session = scan_session_id.is_none() || scan_session_id == scan.scan_session_id;
+        if scan.is_running && scan.is_paused && matches_session {
+            scan.is_paused = false;
+            scan.phase = "scanning".to_string();
+            scan.scan_session_id.clone()
         } else {
-            completion_total += prompt.target_regions.len();
+            None
         }
+    };
+    if let Some(scan_session_id) = unpaused {
+        CHECKPOINT_SAVE_NOW.notify_one();
+        eprintln!("[Scan] Resuming paused scan session {}", scan_session_id);
+        update_tray_status(&app, true);
+        emit_progress_with_state(&app, state.inner());
+        return Ok(scan_session_id);
     }
 
-    Ok(ScanComplete {
-        total_prompts: completion_total * samples * selected_platforms.len(),
-        successful_prompts: total_collected,
-        mention_rate,
-        citation_rate,
-    })
+    let checkpoint = match scan_session_id {
+        Some(id) => storage::load_scan_checkpoint(&id)
+            .ok_or_else(|| format!("No checkpoint found for scan session {}", id))?,
+        None => storage::list_scan_checkpoints()
+            .into_iter()
+            .find(|c| c.status != "complete")
+            .ok_or("No interrupted scan to resume")?,
+    };
+
+    if checkpoint.status == "complete" {
+        return Err("Scan session is already complete".to_string());
+    }
+
+    resume_scan_internal(checkpoint, app, state.inner().clone()).await
+}
+
+/// Pick an unfinished session back up from its checkpoint and return its ID
+pub async fn resume_scan_internal(
+    mut checkpoint: ScanCheckpoint,
+    app: AppHandle,
+    state: Arc<AppState>,
+) -> Result<String, String> {
+    // Ensure we have a valid auth token before picking the session back up
+    crate::commands::auth::ensure_valid_token(&state).await?;
+
+    // Webviews from the previous run are gone, so submitted-but-uncollected cells start over.
+    // Limited cells stay as they are; they were re-queued as their own job.
+    for cell in checkpoint.cells.iter_mut() {
+        if !matches!(cell.status, ScanCellStatus::Collected | ScanCellStatus::Limited) {
+            cell.status = ScanCellStatus::Pending;
+        }
+    }
+    checkpoint.status = "running".to_string();
+    checkpoint.updated_at = chrono::Utc::now().timestamp();
+
+    let scan_session_id = checkpoint.scan_session_id.clone();
+    eprintln!("[Scan] Resuming scan session {}", scan_session_id);
+    launch_scan(app, state, checkpoint)?;
+
+    Ok(scan_session_id)
+}
+
+/// Pause the running scan at the next boundary between cells.
+/// Prompts that were already submitted are still collected; no new prompt starts until
+/// `resume_scan` is called, and waiting lanes hold no webview slot. The checkpoint is
+/// marked "paused" right away. The tray stops showing "Scanning" while paused.
+#[tauri::command]
+pub async fn pause_scan(app: AppHandle, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    {
+        let mut scan = state.scan.lock();
+        if !scan.is_running {
+            return Err("No scan is running".to_string());
+        }
+        if scan.is_paused {
+            return Ok(());
+        }
+        scan.is_paused = true;
+        scan.phase = "paused".to_string();
+    }
+    CHECKPOINT_SAVE_NOW.notify_one();
+
+    eprintln!("[Scan] Scan paused");
+    update_tray_status(&app, false);
+    emit_progress_with_state(&app, state.inner());
+
+    Ok(())
+}
+
+/// Forget an interrupted scan session
+#[tauri::command]
+pub async fn discard_scan_checkpoint(scan_session_id: String) -> Result<(), String> {
+    storage::remove_scan_checkpoint(&scan_session_id)
 }
 
 #[tauri::command]
 pub async fn cancel_scan(app: AppHandle, state: State<'_, Arc<AppState>>) -> Result<(), String> {
     let mut scan = state.scan.lock();
     scan.is_running = false;
+    scan.is_paused = false;
     scan.phase = "cancelled".to_string();
 
     // Reset tray to normal
@@ -706,21 +1815,24 @@ pub async fn is_scan_running(state: State<'_, Arc<AppState>>) -> Result<bool, St
     Ok(scan.is_running)
 }
 
-fn emit_progress_with_state(app: &AppHandle, state: &Arc<AppState>) {
-    let scan = state.scan.lock();
-    let _ = app.emit(
-        "scan:progress",
-        ScanProgressEvent {
-            phase: scan.phase.clone(),
-            current: scan.completed_prompts,
-            total: scan.total_prompts,
-            platforms: scan.platforms.clone(),
-            countdown_seconds: None,
-        },
-    );
+/// Send a `scan:progress` milestone with the current progress to webhooks
+fn notify_progress_milestone(state: &Arc<AppState>, milestone: &str, platform: Option<&str>) {
+    let (scan_session_id, data) = {
+        let scan = state.scan.lock();
+        let data = serde_json::json!({
+            "milestone": milestone,
+            "platform": platform,
+            "productId": scan.product_id,
+            "current": scan.completed_prompts,
+            "total": scan.total_prompts,
+            "platforms": scan.platforms,
+        });
+        (scan.scan_session_id.clone(), data)
+    };
+    webhooks::notify("scan:progress", scan_session_id.as_deref(), data);
 }
 
-fn emit_progress_with_countdown(app: &AppHandle, state: &Arc<AppState>, countdown: usize) {
+fn emit_progress_with_state(app: &AppHandle, state: &Arc<AppState>) {
     let scan = state.scan.lock();
     let _ = app.emit(
         "scan:progress",
@@ -729,7 +1841,7 @@ fn emit_progress_with_countdown(app: &AppHandle, state: &Arc<AppState>, countdow
             current: scan.completed_prompts,
             total: scan.total_prompts,
             platforms: scan.platforms.clone(),
-            countdown_seconds: Some(countdown),
+            countdown_seconds: None,
         },
     );
 }
@@ -1,8 +1,10 @@
-use crate::{storage::{self, ProductConfig}, AppState};
+use crate::{analysis::Competitor, commands::scan::default_scan_platforms, storage::{self, NotificationSettings, ProductConfig, RateLimitPolicy, SnapshotMode, WebhookDelivery, WebhookEndpoint}, webhooks, AppState};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri_plugin_autostart::ManagerExt;
+use uuid::Uuid;
 
 #[derive(Serialize)]
 pub struct ScheduleInfo {
@@ -62,10 +64,23 @@ pub async fn set_product_config(
     time_window_start: u32,
     time_window_end: u32,
     scan_countries: Option<Vec<String>>,
+    max_concurrent_webviews: Option<u32>,
+    snapshot_mode: Option<SnapshotMode>,
+    upload_snapshots: Option<bool>,
+    capture_screenshots: Option<bool>,
+    upload_screenshots: Option<bool>,
+    brand_aliases: Option<Vec<String>>,
+    brand_exclusions: Option<Vec<String>>,
+    brand_case_sensitive: Option<bool>,
+    competitor_terms: Option<Vec<Competitor>>,
 ) -> Result<ProductConfig, String> {
     println!("[Settings] set_product_config for {}: platforms={:?}, auto_run={}, scans_per_day={}, window={}-{}, countries={:?}",
         product_id, ready_platforms, auto_run_enabled, scans_per_day, time_window_start, time_window_end, scan_countries);
 
+    if capture_screenshots == Some(true) && !crate::webview::ANSWER_RENDER_SUPPORTED {
+        return Err("Answer images are only available on Windows".to_string());
+    }
+
     let existing = storage::get_product_config(&product_id);
 
     let new_scans_per_day = scans_per_day.max(1).min(24);
@@ -75,6 +90,10 @@ pub async fn set_product_config(
     // Use provided scan_countries or preserve existing
     let new_scan_countries = scan_countries.unwrap_or(existing.scan_countries);
 
+    let new_max_concurrent_webviews = max_concurrent_webviews
+        .unwrap_or(existing.max_concurrent_webviews)
+        .clamp(1, 16);
+
     // Check if schedule-affecting settings changed
     let schedule_changed = existing.scans_per_day != new_scans_per_day
         || existing.time_window_start != new_time_window_start
@@ -103,6 +122,15 @@ pub async fn set_product_config(
         scans_today,
         scheduled_times,
         scan_countries: new_scan_countries,
+        max_concurrent_webviews: new_max_concurrent_webviews,
+        snapshot_mode: snapshot_mode.unwrap_or(existing.snapshot_mode),
+        upload_snapshots: upload_snapshots.unwrap_or(existing.upload_snapshots),
+        capture_screenshots: capture_screenshots.unwrap_or(existing.capture_screenshots),
+        upload_screenshots: upload_screenshots.unwrap_or(existing.upload_screenshots),
+        brand_aliases: brand_aliases.unwrap_or(existing.brand_aliases),
+        brand_exclusions: brand_exclusions.unwrap_or(existing.brand_exclusions),
+        brand_case_sensitive: brand_case_sensitive.unwrap_or(existing.brand_case_sensitive),
+        competitor_terms: competitor_terms.unwrap_or(existing.competitor_terms),
     };
 
     // Persist config to disk
@@ -215,3 +243,98 @@ pub async fn set_autostart_enabled(app: AppHandle, enabled: bool) -> Result<bool
         .is_enabled()
         .map_err(|e| format!("Failed to verify autostart status: {}", e))
 }
+
+/// Rate-limit policy in effect for each given platform (defaults to the scan platforms)
+#[tauri::command]
+pub async fn get_rate_limits(platforms: Option<Vec<String>>) -> Result<HashMap<String, RateLimitPolicy>, String> {
+    Ok(platforms
+        .unwrap_or_else(default_scan_platforms)
+        .into_iter()
+        .map(|platform| {
+            let policy = storage::get_rate_limit(&platform);
+            (platform, policy)
+        })
+        .collect())
+}
+
+/// Set a platform's rate-limit policy; without a policy the built-in default applies again
+#[tauri::command]
+pub async fn set_rate_limit(platform: String, policy: Option<RateLimitPolicy>) -> Result<RateLimitPolicy, String> {
+    println!("[Settings] set_rate_limit for {}: {:?}", platform, policy);
+    storage::set_rate_limit(&platform, policy)?;
+    Ok(storage::get_rate_limit(&platform))
+}
+
+#[tauri::command]
+pub async fn get_webhooks() -> Result<Vec<WebhookEndpoint>, String> {
+    Ok(storage::get_webhooks())
+}
+
+/// Add or update a webhook endpoint. A new endpoint gets an ID, and a random secret if none is given.
+#[tauri::command]
+pub async fn save_webhook(mut endpoint: WebhookEndpoint) -> Result<WebhookEndpoint, String> {
+    let url = reqwest::Url::parse(&endpoint.url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("Webhook URL must use http or https".to_string());
+    }
+    if endpoint.id.is_empty() {
+        endpoint.id = Uuid::new_v4().to_string();
+    }
+    if endpoint.secret.is_empty() {
+        endpoint.secret = Uuid::new_v4().simple().to_string();
+    }
+
+    println!("[Settings] save_webhook {} -> {}", endpoint.id, endpoint.url);
+    storage::save_webhook(endpoint.clone())?;
+    Ok(endpoint)
+}
+
+#[tauri::command]
+pub async fn remove_webhook(id: String) -> Result<(), String> {
+    println!("[Settings] remove_webhook {}", id);
+    storage::remove_webhook(&id)
+}
+
+/// Webhook delivery log, newest first
+#[tauri::command]
+pub async fn get_webhook_deliveries(limit: Option<usize>) -> Result<Vec<WebhookDelivery>, String> {
+    let mut deliveries = storage::load_webhook_deliveries();
+    deliveries.reverse();
+    deliveries.truncate(limit.unwrap_or(usize::MAX));
+    Ok(deliveries)
+}
+
+/// Send pending webhook deliveries now instead of waiting for their backoff
+#[tauri::command]
+pub async fn retry_webhook_deliveries() -> Result<(), String> {
+    webhooks::flush_deliveries(true).await;
+    Ok(())
+}
+
+/// Send a `webhook:test` event to one endpoint; the result shows up in the delivery log
+#[tauri::command]
+pub async fn test_webhook(id: String) -> Result<(), String> {
+    let endpoint = storage::get_webhooks()
+        .into_iter()
+        .find(|w| w.id == id)
+        .ok_or("Webhook not found")?;
+    if !endpoint.enabled {
+        return Err("Webhook is disabled".to_string());
+    }
+
+    webhooks::notify_endpoint(&endpoint, "webhook:test", None, serde_json::json!({ "message": "Test delivery from Columbus" }));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_notification_settings() -> Result<NotificationSettings, String> {
+    Ok(storage::get_notification_settings())
+}
+
+/// Choose which events show a desktop notification
+#[tauri::command]
+pub async fn set_notification_settings(settings: NotificationSettings) -> Result<NotificationSettings, String> {
+    println!("[Settings] set_notification_settings: {:?}", settings);
+    storage::set_notification_settings(settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,278 @@
+//! Export of locally stored scan results to CSV, JSONL and Parquet
+//!
+//! Flattening rules:
+//! - JSONL: one `HistoryEntry` per line, exactly as `query_scan_history` returns it
+//!   (camelCase keys, `citations` and `competitorDetails` kept as nested arrays).
+//! - CSV and Parquet: one row per result with the snake_case columns in `COLUMNS`.
+//!   Nested lists become parallel columns holding JSON arrays, e.g. `citation_urls`
+//!   `["https://a","https://b"]` and `citation_titles` `["A","B"]` line up by index.
+//! - Conversation turns of prompts with follow-ups become `turn_prompts` and
+//!   `turn_responses` (the first turn included); both are `[]` for single-turn prompts.
+
+use crate::scan_history::HistoryEntry;
+use parquet::basic::Compression;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+#[derive(Clone, Copy)]
+enum ColumnKind {
+    Text,
+    OptionalText,
+    Int,
+    OptionalInt,
+    OptionalFloat,
+    Bool,
+}
+
+/// Flat columns used by CSV and Parquet, in output order
+const COLUMNS: &[(&str, ColumnKind)] = &[
+    ("scan_session_id", ColumnKind::Text),
+    ("product_id", ColumnKind::Text),
+    ("prompt_id", ColumnKind::Text),
+    ("prompt_text", ColumnKind::Text),
+    ("prompt_variant", ColumnKind::OptionalText),
+    ("platform", ColumnKind::Text),
+    ("country", ColumnKind::Text),
+    ("sample", ColumnKind::Int),
+    ("submitted_at", ColumnKind::Int),
+    ("collected_at", ColumnKind::Int),
+    ("response_wait_ms", ColumnKind::OptionalInt),
+    ("response_timed_out", ColumnKind::Bool),
+    ("brand_mentioned", ColumnKind::Bool),
+    ("citation_present", ColumnKind::Bool),
+    ("position", ColumnKind::OptionalInt),
+    ("sentiment", ColumnKind::Text),
+    ("sentiment_score", ColumnKind::OptionalFloat),
+    ("credits_exhausted", ColumnKind::Bool),
+    ("chat_url", ColumnKind::OptionalText),
+    ("response_text", ColumnKind::Text),
+    ("turn_prompts", ColumnKind::Text),
+    ("turn_responses", ColumnKind::Text),
+    ("competitor_mentions", ColumnKind::Text),
+    ("competitor_names", ColumnKind::Text),
+    ("competitor_positions", ColumnKind::Text),
+    ("competitor_sentiments", ColumnKind::Text),
+    ("competitor_sentiment_scores", ColumnKind::Text),
+    ("citation_count", ColumnKind::Int),
+    ("citation_urls", ColumnKind::Text),
+    ("citation_titles", ColumnKind::Text),
+    ("citation_positions", ColumnKind::Text),
+];
+
+enum Cell {
+    Text(String),
+    OptionalText(Option<String>),
+    Int(i64),
+    OptionalInt(Option<i64>),
+    OptionalFloat(Option<f64>),
+    Bool(bool),
+}
+
+impl Cell {
+    fn to_csv_field(&self) -> String {
+        match self {
+            Cell::Text(s) => s.clone(),
+            Cell::OptionalText(s) => s.clone().unwrap_or_default(),
+            Cell::Int(i) => i.to_string(),
+            Cell::OptionalInt(i) => i.map(|i| i.to_string()).unwrap_or_default(),
+            Cell::OptionalFloat(f) => f.map(|f| f.to_string()).unwrap_or_default(),
+            Cell::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+fn json_list<T: serde::Serialize>(items: &[T]) -> String {
+    serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Flatten one entry into the cells of `COLUMNS`
+fn flatten_entry(entry: &HistoryEntry) -> Vec<Cell> {
+    let r = &entry.result;
+    let competitor_names: Vec<&str> = r.competitor_details.iter().map(|c| c.name.as_str()).collect();
+    let competitor_positions: Vec<Option<i32>> = r.competitor_details.iter().map(|c| c.position).collect();
+    let competitor_sentiments: Vec<&str> = r.competitor_details.iter().map(|c| c.sentiment.as_str()).collect();
+    let competitor_sentiment_scores: Vec<Option<f64>> = r.competitor_details.iter().map(|c| c.sentiment_score).collect();
+    let citation_urls: Vec<&str> = r.citations.iter().map(|c| c.url.as_str()).collect();
+    let citation_titles: Vec<&str> = r.citations.iter().map(|c| c.title.as_str()).collect();
+    let citation_positions: Vec<i32> = r.citations.iter().map(|c| c.position).collect();
+    let turn_prompts: Vec<&str> = r.turns.iter().map(|t| t.prompt_text.as_str()).collect();
+    let turn_responses: Vec<&str> = r.turns.iter().map(|t| t.response_text.as_str()).collect();
+
+    vec![
+        Cell::Text(r.scan_session_id.clone()),
+        Cell::Text(r.product_id.clone()),
+        Cell::Text(r.prompt_id.clone()),
+        Cell::Text(r.prompt_text.clone()),
+        Cell::OptionalText(r.prompt_variant.clone()),
+        Cell::Text(r.platform.clone()),
+        Cell::Text(r.request_country.clone().unwrap_or_else(|| "local".to_string())),
+        Cell::Int(entry.sample as i64),
+        Cell::Int(entry.submitted_at),
+        Cell::Int(entry.collected_at),
+        Cell::OptionalInt(r.response_wait_ms.map(|ms| ms as i64)),
+        Cell::Bool(r.response_timed_out),
+        Cell::Bool(r.brand_mentioned),
+        Cell::Bool(r.citation_present),
+        Cell::OptionalInt(r.position.map(i64::from)),
+        Cell::Text(r.sentiment.clone()),
+        Cell::OptionalFloat(r.sentiment_score),
+        Cell::Bool(r.credits_exhausted),
+        Cell::OptionalText(r.chat_url.clone()),
+        Cell::Text(r.response_text.clone()),
+        Cell::Text(json_list(&turn_prompts)),
+        Cell::Text(json_list(&turn_responses)),
+        Cell::Text(json_list(&r.competitor_mentions)),
+        Cell::Text(json_list(&competitor_names)),
+        Cell::Text(json_list(&competitor_positions)),
+        Cell::Text(json_list(&competitor_sentiments)),
+        Cell::Text(json_list(&competitor_sentiment_scores)),
+        Cell::Int(r.citations.len() as i64),
+        Cell::Text(json_list(&citation_urls)),
+        Cell::Text(json_list(&citation_titles)),
+        Cell::Text(json_list(&citation_positions)),
+    ]
+}
+
+/// Write entries to `path` in the given format
+pub fn write_export(entries: &[HistoryEntry], format: ExportFormat, path: &Path) -> Result<(), String> {
+    match format {
+        ExportFormat::Csv => write_csv(entries, path),
+        ExportFormat::Jsonl => write_jsonl(entries, path),
+        ExportFormat::Parquet => write_parquet(entries, path),
+    }
+}
+
+fn write_csv(entries: &[HistoryEntry], path: &Path) -> Result<(), String> {
+    let mut writer = csv::Writer::from_path(path).map_err(|e| format!("Failed to create CSV file: {}", e))?;
+
+    writer
+        .write_record(COLUMNS.iter().map(|(name, _)| *name))
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+
+    for entry in entries {
+        let fields: Vec<String> = flatten_entry(entry).iter().map(Cell::to_csv_field).collect();
+        writer.write_record(&fields).map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    writer.flush().map_err(|e| format!("Failed to write CSV file: {}", e))
+}
+
+fn write_jsonl(entries: &[HistoryEntry], path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create JSONL file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+
+    for entry in entries {
+        serde_json::to_writer(&mut writer, entry).map_err(|e| format!("Failed to write JSONL row: {}", e))?;
+        writer.write_all(b"\n").map_err(|e| format!("Failed to write JSONL file: {}", e))?;
+    }
+
+    writer.flush().map_err(|e| format!("Failed to write JSONL file: {}", e))
+}
+
+/// Parquet schema matching `COLUMNS`
+fn parquet_schema() -> String {
+    let fields: Vec<String> = COLUMNS
+        .iter()
+        .map(|(name, kind)| match kind {
+            ColumnKind::Text => format!("REQUIRED BYTE_ARRAY {} (UTF8);", name),
+            ColumnKind::OptionalText => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", name),
+            ColumnKind::Int => format!("REQUIRED INT64 {};", name),
+            ColumnKind::OptionalInt => format!("OPTIONAL INT64 {};", name),
+            ColumnKind::OptionalFloat => format!("OPTIONAL DOUBLE {};", name),
+            ColumnKind::Bool => format!("REQUIRED BOOLEAN {};", name),
+        })
+        .collect();
+    format!("message scan_result {{ {} }}", fields.join(" "))
+}
+
+fn write_parquet(entries: &[HistoryEntry], path: &Path) -> Result<(), String> {
+    let parquet_err = |e: parquet::errors::ParquetError| format!("Failed to write Parquet file: {}", e);
+
+    let schema = Arc::new(parse_message_type(&parquet_schema()).map_err(parquet_err)?);
+    let props = Arc::new(WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+    let file = File::create(path).map_err(|e| format!("Failed to create Parquet file: {}", e))?;
+    let mut writer = SerializedFileWriter::new(file, schema, props).map_err(parquet_err)?;
+
+    let rows: Vec<Vec<Cell>> = entries.iter().map(flatten_entry).collect();
+    let mut row_group = writer.next_row_group().map_err(parquet_err)?;
+    let mut column_index = 0;
+
+    while let Some(mut column) = row_group.next_column().map_err(parquet_err)? {
+        let cells = rows.iter().map(|row| &row[column_index]);
+        let kind = COLUMNS[column_index].1;
+        // Definition levels only exist for optional columns
+        let optional = matches!(kind, ColumnKind::OptionalText | ColumnKind::OptionalInt | ColumnKind::OptionalFloat);
+
+        match kind {
+            ColumnKind::Text | ColumnKind::OptionalText => {
+                let mut values = Vec::new();
+                let mut def_levels = Vec::new();
+                for cell in cells {
+                    let value = match cell {
+                        Cell::Text(s) => Some(s.as_str()),
+                        Cell::OptionalText(s) => s.as_deref(),
+                        _ => None,
+                    };
+                    def_levels.push(value.is_some() as i16);
+                    if let Some(v) = value {
+                        values.push(ByteArray::from(v));
+                    }
+                }
+                column.typed::<ByteArrayType>().write_batch(&values, optional.then_some(&def_levels[..]), None).map_err(parquet_err)?;
+            }
+            ColumnKind::Int | ColumnKind::OptionalInt => {
+                let mut values = Vec::new();
+                let mut def_levels = Vec::new();
+                for cell in cells {
+                    let value = match cell {
+                        Cell::Int(i) => Some(*i),
+                        Cell::OptionalInt(i) => *i,
+                        _ => None,
+                    };
+                    def_levels.push(value.is_some() as i16);
+                    values.extend(value);
+                }
+                column.typed::<Int64Type>().write_batch(&values, optional.then_some(&def_levels[..]), None).map_err(parquet_err)?;
+            }
+            ColumnKind::OptionalFloat => {
+                let mut values = Vec::new();
+                let mut def_levels = Vec::new();
+                for cell in cells {
+                    let value = match cell {
+                        Cell::OptionalFloat(f) => *f,
+                        _ => None,
+                    };
+                    def_levels.push(value.is_some() as i16);
+                    values.extend(value);
+                }
+                column.typed::<DoubleType>().write_batch(&values, Some(&def_levels[..]), None).map_err(parquet_err)?;
+            }
+            ColumnKind::Bool => {
+                let values: Vec<bool> = cells.map(|cell| matches!(cell, Cell::Bool(true))).collect();
+                column.typed::<BoolType>().write_batch(&values, None, None).map_err(parquet_err)?;
+            }
+        }
+
+        column.close().map_err(parquet_err)?;
+        column_index += 1;
+    }
+
+    row_group.close().map_err(parquet_err)?;
+    writer.close().map_err(parquet_err)?;
+    Ok(())
+}
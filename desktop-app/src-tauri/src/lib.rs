@@ -8,10 +8,20 @@ use tauri::{
     Manager, WindowEvent,
 };
 
+mod analysis;
 mod autoscan;
 mod commands;
+mod export;
+mod notifications;
+mod outbox;
+mod prompt_template;
 mod proxy_server;
+mod rate_limit;
+mod reanalysis;
+mod scan_history;
+mod scan_queue;
 mod storage;
+mod webhooks;
 mod webview;
 
 pub use commands::*;
@@ -81,6 +91,8 @@ pub struct User {
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct ScanState {
     pub is_running: bool,
+    /// Set by `pause_scan`; lanes stop starting new cells until it is cleared
+    pub is_paused: bool,
     pub phase: String,
     pub scan_session_id: Option<String>,
     pub product_id: Option<String>,
@@ -91,11 +103,17 @@ pub struct ScanState {
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct PlatformState {
-    pub status: String,  // pending, submitting, waiting, collecting, complete, skipped
+    pub status: String,  // pending, submitting, waiting, collecting, complete, skipped, limited
     pub total: usize,
     pub submitted: usize,
     pub collected: usize,
     pub failed: usize,
+    /// Countries whose account hit a usage limit during this scan
+    #[serde(default)]
+    pub limited_countries: Vec<String>,
+    /// Unix seconds when the latest limit lifts, if the platform said
+    #[serde(default)]
+    pub limited_until: Option<i64>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -113,6 +131,69 @@ pub struct Prompt {
     pub category: Option<String>,
     #[serde(default)]
     pub target_regions: Vec<String>,
+    /// Messages sent in the same chat after the answer to `text`, in order
+    #[serde(default)]
+    pub follow_ups: Vec<String>,
+    /// Translations keyed by "language-country" (e.g. "pt-br"), country code or language code
+    #[serde(default)]
+    pub translations: HashMap<String, PromptTranslation>,
+    /// Translation key this copy's text was taken from; set by the scanner, not the API
+    #[serde(skip)]
+    pub variant: Option<String>,
+}
+
+/// A prompt's text in another language
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTranslation {
+    pub text: String,
+    /// Translated follow-ups; the base ones are used if this is empty
+    #[serde(default)]
+    pub follow_ups: Vec<String>,
+}
+
+/// Restricts a scan to a subset of the product's prompts.
+/// Empty lists don't restrict anything; all set criteria must match.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFilter {
+    #[serde(default)]
+    pub prompt_ids: Vec<String>,
+    /// Matched against `Prompt.category` (case-insensitive)
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Only run prompts in these target regions ("local" = prompts without target regions)
+    #[serde(default)]
+    pub countries: Vec<String>,
+    /// Only prompts that had a failed or timed-out cell in the product's last scan session
+    #[serde(default)]
+    pub only_failed_last_session: bool,
+}
+
+impl PromptFilter {
+    pub fn matches(&self, prompt: &Prompt) -> bool {
+        let id_matches = self.prompt_ids.is_empty() || self.prompt_ids.contains(&prompt.id);
+        let category_matches = self.categories.is_empty()
+            || prompt.category.as_ref().is_some_and(|c| {
+                self.categories.iter().any(|wanted| wanted.eq_ignore_ascii_case(c))
+            });
+        id_matches && category_matches
+    }
+
+    /// Narrow a prompt's target regions to the filtered countries.
+    /// Returns false if the prompt has nothing left to run.
+    pub fn restrict_regions(&self, prompt: &mut Prompt) -> bool {
+        if self.countries.is_empty() {
+            return true;
+        }
+        let wanted = |region: &str| self.countries.iter().any(|c| c.eq_ignore_ascii_case(region));
+
+        if prompt.target_regions.is_empty() {
+            return wanted("local");
+        }
+        prompt.target_regions.retain(|r| wanted(r));
+        !prompt.target_regions.is_empty()
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -130,6 +211,33 @@ pub struct CompetitorDetailResult {
     pub name: String,
     pub position: Option<i32>,
     pub sentiment: String,
+    /// From -1 (negative) to 1 (positive)
+    #[serde(default)]
+    pub sentiment_score: Option<f64>,
+    /// Names, aliases, products or domains that matched, in order of first appearance
+    #[serde(default)]
+    pub matched_aliases: Vec<String>,
+    /// Times the competitor is mentioned in the answer
+    #[serde(default)]
+    pub mentions: usize,
+    /// Where it is mentioned in the response text
+    #[serde(default)]
+    pub spans: Vec<TextSpan>,
+}
+
+impl CompetitorDetailResult {
+    /// A mentioned competitor without position, sentiment or match details
+    pub fn unrated(name: &str) -> Self {
+        CompetitorDetailResult {
+            name: name.to_string(),
+            position: None,
+            sentiment: "neutral".to_string(),
+            sentiment_score: None,
+            matched_aliases: Vec::new(),
+            mentions: 0,
+            spans: Vec::new(),
+        }
+    }
 }
 
 // ScanResult uses camelCase to match the Supabase API expected format
@@ -143,15 +251,49 @@ pub struct ScanResult {
     pub prompt_text: String,
     pub response_text: String,
     pub brand_mentioned: bool,
+    /// Where the brand is mentioned in `response_text`
+    #[serde(default)]
+    pub brand_spans: Vec<TextSpan>,
     pub citation_present: bool,
     pub position: Option<i32>,
     pub sentiment: String,
+    /// From -1 (negative) to 1 (positive), None if the brand isn't mentioned
+    #[serde(default)]
+    pub sentiment_score: Option<f64>,
     pub competitor_mentions: Vec<String>,
     pub competitor_details: Vec<CompetitorDetailResult>,
     pub citations: Vec<Citation>,
     pub credits_exhausted: bool,
     pub chat_url: Option<String>,
     pub request_country: Option<String>,
+    /// Milliseconds from prompt submission until the answer was detected as final
+    #[serde(default)]
+    pub response_wait_ms: Option<u64>,
+    /// True if the platform's maximum wait ran out before the answer settled
+    #[serde(default)]
+    pub response_timed_out: bool,
+    /// Storage object path (not a URL) of the answer's PNG screenshot, if one was uploaded.
+    /// Outbox entries written before the rename still carry it as `screenshotUrl`.
+    #[serde(default, alias = "screenshotUrl")]
+    pub screenshot_object_path: Option<String>,
+    /// Every turn of a prompt with follow-ups, the first one included; empty otherwise.
+    /// With turns, `response_text` is the first answer while `brand_mentioned`,
+    /// `citation_present`, `competitor_mentions` and `citations` cover the whole conversation.
+    #[serde(default)]
+    pub turns: Vec<ConversationTurn>,
+    /// Translation key the submitted text came from (e.g. "de" or "pt-br"); None for the base text
+    #[serde(default)]
+    pub prompt_variant: Option<String>,
+    /// Identity of the cell the result came from (see `result_key`). The API updates the
+    /// result stored under the same key instead of adding another one; outbox entries
+    /// written before it existed have none.
+    #[serde(default)]
+    pub result_key: Option<String>,
+}
+
+/// Key identifying one cell's result across retries and re-analysis
+pub fn result_key(scan_session_id: &str, country_code: &str, platform: &str, prompt_id: &str, sample: usize) -> String {
+    format!("{}/{}/{}/{}/{}", scan_session_id, country_code, platform, prompt_id, sample)
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -162,12 +304,79 @@ pub struct Citation {
     pub position: i32,
 }
 
+/// Where a name was found in a response text
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextSpan {
+    /// Start and end (exclusive) as JavaScript string indices (UTF-16 code units)
+    pub start: usize,
+    pub end: usize,
+    /// The text as written in the response
+    pub text: String,
+}
+
+/// One message of a multi-turn prompt and the answer to it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationTurn {
+    pub prompt_text: String,
+    pub response_text: String,
+    pub brand_mentioned: bool,
+    /// Where the brand is mentioned in `response_text`
+    #[serde(default)]
+    pub brand_spans: Vec<TextSpan>,
+    /// Rank of the brand in this answer's lists
+    #[serde(default)]
+    pub position: Option<i32>,
+    /// Sentiment towards the brand in this answer, from -1 to 1
+    #[serde(default)]
+    pub sentiment_score: Option<f64>,
+    pub citation_present: bool,
+    pub competitor_mentions: Vec<String>,
+    /// Competitors mentioned in this answer, with spans in its `response_text`
+    #[serde(default)]
+    pub competitor_details: Vec<CompetitorDetailResult>,
+    /// Citations that first appeared with this answer
+    pub citations: Vec<Citation>,
+    pub response_wait_ms: Option<u64>,
+    pub response_timed_out: bool,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ScanComplete {
     pub total_prompts: usize,
     pub successful_prompts: usize,
     pub mention_rate: f64,
     pub citation_rate: f64,
+    #[serde(default)]
+    pub by_platform: Vec<ScanBreakdown>,
+    #[serde(default)]
+    pub by_country: Vec<ScanBreakdown>,
+    #[serde(default)]
+    pub by_platform_country: Vec<ScanBreakdown>,
+}
+
+/// Scan results for one platform, one country, or one platform × country pair
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScanBreakdown {
+    pub platform: Option<String>,
+    pub country: Option<String>,
+    pub collected: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Cells deferred because the account hit a usage limit
+    pub limited: usize,
+    pub mention_rate: f64,
+    pub citation_rate: f64,
+    pub credits_exhausted: usize,
+    /// Most often mentioned competitors, most mentions first
+    pub top_competitors: Vec<CompetitorCount>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompetitorCount {
+    pub name: String,
+    pub mentions: usize,
 }
 
 /// Update tray icon and tooltip based on scan state
@@ -253,12 +462,15 @@ pub fn run() {
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             // When a second instance tries to run, show the existing window
             println!("[Columbus] Second instance detected, focusing existing window");
+            // On Windows this is also how a notification click arrives
+            notifications::on_relaunched(app);
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
                 let _ = window.set_focus();
             }
         }))
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--minimized"]),
@@ -275,10 +487,26 @@ pub fn run() {
             commands::api::finalize_scan,
             commands::api::get_ai_platforms,
             commands::api::get_prompt_target_regions,
+            commands::api::get_outbox_status,
+            commands::api::retry_outbox_now,
             commands::scan::start_scan,
+            commands::scan::plan_scan,
             commands::scan::cancel_scan,
             commands::scan::get_scan_progress,
             commands::scan::is_scan_running,
+            commands::scan::get_resumable_scans,
+            commands::scan::resume_scan,
+            commands::scan::pause_scan,
+            commands::scan::discard_scan_checkpoint,
+            commands::history::query_scan_history,
+            commands::history::get_history_sessions,
+            commands::history::export_scan_session,
+            commands::history::upload_scan_snapshots,
+            commands::history::reanalyze_session,
+            commands::queue::enqueue_scan,
+            commands::queue::get_scan_queue,
+            commands::queue::reorder_scan_queue,
+            commands::queue::remove_scan_job,
             commands::platform::open_platform_login,
             commands::platform::close_platform_login,
             commands::platform::open_url_in_browser,
@@ -289,6 +517,16 @@ pub fn run() {
             commands::settings::set_last_product_id,
             commands::settings::get_autostart_enabled,
             commands::settings::set_autostart_enabled,
+            commands::settings::get_rate_limits,
+            commands::settings::set_rate_limit,
+            commands::settings::get_webhooks,
+            commands::settings::save_webhook,
+            commands::settings::remove_webhook,
+            commands::settings::get_webhook_deliveries,
+            commands::settings::retry_webhook_deliveries,
+            commands::settings::test_webhook,
+            commands::settings::get_notification_settings,
+            commands::settings::set_notification_settings,
             commands::proxy::fetch_proxy_config,
             commands::proxy::has_proxy_config,
             commands::proxy::clear_proxy_config,
@@ -385,14 +623,32 @@ pub fn run() {
             println!("[Columbus] Starting auto-scan scheduler...");
             autoscan::start_scheduler(app.handle().clone());
 
+            // Start the outbox uploader (retries scan results that failed to upload)
+            println!("[Columbus] Starting outbox uploader...");
+            outbox::start_outbox_worker(app.handle().clone());
+
+            // Start the scan queue runner (picks up jobs left from a previous run)
+            println!("[Columbus] Starting scan queue runner...");
+            scan_queue::start_queue_runner(app.handle().clone());
+
+            // Start the webhook worker (retries deliveries that failed)
+            println!("[Columbus] Starting webhook worker...");
+            webhooks::start_webhook_worker();
+
             println!("[Columbus] Setup complete");
             Ok(())
         })
         .on_window_event(|window, event| {
-            // Hide window instead of closing when user clicks X
-            if let WindowEvent::CloseRequested { api, .. } = event {
-                let _ = window.hide();
-                api.prevent_close();
+            match event {
+                // Hide window instead of closing when user clicks X
+                WindowEvent::CloseRequested { api, .. } => {
+                    let _ = window.hide();
+                    api.prevent_close();
+                }
+                WindowEvent::Focused(true) if window.label() == "main" => {
+                    notifications::on_main_window_focused();
+                }
+                _ => {}
             }
         })
         .run(tauri::generate_context!())
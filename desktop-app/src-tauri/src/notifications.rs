@@ -0,0 +1,141 @@
+//! Native desktop notifications for scan outcomes and events that need the user
+//!
+//! Each kind of notification can be turned off in `NotificationSettings`. The same kind
+//! and subject (e.g. logged out on "de:chatgpt") is shown at most once per
+//! `REPEAT_AFTER_SECS`, so a lane that keeps failing doesn't flood the notification center.
+//!
+//! The notification plugin has no click callback on desktop. The one real click signal is
+//! on Windows, where clicking a toast relaunches the app and the single-instance plugin
+//! hands that to the running instance. So the screen of the last notification is
+//! remembered, and a relaunch within `NAVIGATE_WITHIN_SECS` shows the window and sends the
+//! frontend a `notification:navigate` event with that screen. Merely focusing the window
+//! (e.g. alt-tabbing back) never navigates; it drops the remembered screen instead, since
+//! the user has seen the app by then. Elsewhere a click just brings the app to the front.
+
+use crate::commands::platform::platform_display_name;
+use crate::storage::{self, NotificationSettings};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Same kind and subject within this window is shown once
+const REPEAT_AFTER_SECS: i64 = 600;
+
+/// A relaunch later than this isn't taken as a click on the notification
+const NAVIGATE_WITHIN_SECS: i64 = 300;
+
+lazy_static::lazy_static! {
+    /// When each "{kind}:{subject}" was last shown
+    static ref LAST_SHOWN: Mutex<HashMap<String, i64>> = Mutex::new(HashMap::new());
+    /// Screen of the last notification and when it was shown
+    static ref PENDING_SCREEN: Mutex<Option<(&'static str, i64)>> = Mutex::new(None);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    ScanComplete,
+    ScanFailed,
+    Captcha,
+    LoggedOut,
+    CreditsExhausted,
+}
+
+impl NotificationKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::ScanComplete => "scan_complete",
+            NotificationKind::ScanFailed => "scan_failed",
+            NotificationKind::Captcha => "captcha",
+            NotificationKind::LoggedOut => "logged_out",
+            NotificationKind::CreditsExhausted => "credits_exhausted",
+        }
+    }
+
+    fn enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            NotificationKind::ScanComplete => settings.scan_complete,
+            NotificationKind::ScanFailed => settings.scan_failed,
+            NotificationKind::Captcha => settings.captcha,
+            NotificationKind::LoggedOut => settings.logged_out,
+            NotificationKind::CreditsExhausted => settings.credits_exhausted,
+        }
+    }
+
+    /// Frontend view that clicking the notification opens (see `showView` in main.js)
+    fn screen(self) -> &'static str {
+        match self {
+            NotificationKind::ScanComplete => "complete",
+            NotificationKind::ScanFailed => "main",
+            // Captchas and logins are dealt with by opening the platform per country
+            NotificationKind::Captcha | NotificationKind::LoggedOut => "region-auth",
+            NotificationKind::CreditsExhausted => "scanning",
+        }
+    }
+}
+
+/// Show a notification unless its kind is turned off or the same `subject` was just shown
+pub fn notify(app: &AppHandle, kind: NotificationKind, subject: &str, title: &str, body: &str) {
+    if !kind.enabled(&storage::get_notification_settings()) {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    {
+        let mut last_shown = LAST_SHOWN.lock();
+        let key = format!("{}:{}", kind.as_str(), subject);
+        if last_shown.get(&key).is_some_and(|t| now - t < REPEAT_AFTER_SECS) {
+            return;
+        }
+        last_shown.retain(|_, t| now - *t < REPEAT_AFTER_SECS);
+        last_shown.insert(key, now);
+    }
+
+    println!("[Notifications] {}: {}", title, body);
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("[Notifications] Failed to show notification: {}", e);
+        return;
+    }
+
+    // A window the user is looking at won't be focused by the click
+    let main_focused = app
+        .get_webview_window("main")
+        .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false));
+    *PENDING_SCREEN.lock() = if main_focused { None } else { Some((kind.screen(), now)) };
+}
+
+/// Called when a second instance is launched, which on Windows is how a notification click
+/// arrives: open the screen of a recent notification
+pub fn on_relaunched(app: &AppHandle) {
+    let Some((screen, shown_at)) = PENDING_SCREEN.lock().take() else {
+        return;
+    };
+    if chrono::Utc::now().timestamp() - shown_at > NAVIGATE_WITHIN_SECS {
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+    }
+    let _ = app.emit("notification:navigate", screen);
+}
+
+/// Called when the main window gains focus: the user is back in the app, so a later
+/// relaunch is no longer taken as a click on the last notification
+pub fn on_main_window_focused() {
+    PENDING_SCREEN.lock().take();
+}
+
+/// "ChatGPT (US)" for notification text
+pub fn lane_label(country_code: &str, platform: &str) -> String {
+    format!("{} ({})", platform_display_name(platform), country_label(country_code))
+}
+
+fn country_label(country_code: &str) -> String {
+    if country_code == "local" {
+        "local".to_string()
+    } else {
+        country_code.to_uppercase()
+    }
+}
@@ -0,0 +1,219 @@
+//! Durable outbox for scan uploads
+//!
+//! Scan results (and the finalize call that follows them) that the API did not accept
+//! are written to `outbox.json` in the config directory. A background task retries them
+//! with exponential backoff, so a dropped connection or an expired token mid-scan no
+//! longer loses the collected data.
+//!
+//! Uploads the API rejects for good (a 4xx other than 408 and 429), or that still fail
+//! after `MAX_OUTBOX_ATTEMPTS`, are moved to `outbox_rejected.json` instead, so they don't
+//! retry forever or keep their session from being finalized.
+
+use crate::commands::api::{post_finalize_scan, post_scan_result, UploadError};
+use crate::storage::{self, OutboxPayload, MAX_OUTBOX_ATTEMPTS};
+use crate::{AppState, ScanResult};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tokio::time::{interval, Duration};
+
+lazy_static::lazy_static! {
+    /// Only one flush may walk the outbox at a time (timer tick vs. manual retry)
+    static ref FLUSH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// Summary of pending uploads, shown in the UI and emitted as `outbox:updated`
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxStatus {
+    pub pending_results: usize,
+    pub pending_finalizations: usize,
+    /// Creation time of the oldest pending entry (unix seconds)
+    pub oldest_created_at: Option<i64>,
+    /// When the next automatic retry is due (unix seconds)
+    pub next_attempt_at: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// Start the background uploader that drains the outbox
+pub fn start_outbox_worker(app: AppHandle) {
+    async_runtime::spawn(async move {
+        // Give auth state a moment to load, then retry anything left from a previous run
+        tokio::time::sleep(Duration::from_secs(15)).await;
+
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            flush_outbox(&app, false).await;
+        }
+    });
+}
+
+/// Upload a scan result, parking it in the outbox if the API does not accept it
+pub async fn submit_or_enqueue(app: &AppHandle, state: &Arc<AppState>, result: &ScanResult) {
+    eprintln!("Submitting scan result for {} prompt {} to API...", result.platform, result.prompt_id);
+
+    let outcome = match crate::commands::auth::ensure_valid_token(state).await {
+        Ok(token) => post_scan_result(&token, result).await,
+        Err(e) => Err(UploadError::retryable(format!("No valid auth token: {}", e))),
+    };
+
+    match outcome {
+        Ok(()) => eprintln!("API submission successful"),
+        Err(e) if e.permanent => {
+            eprintln!("API rejected the result, setting it aside: {}", e);
+            let payload = OutboxPayload::ScanResult { result: Box::new(result.clone()) };
+            if let Err(store_err) = storage::store_rejected_upload(payload, 1, &e.message) {
+                eprintln!("[Outbox] Failed to keep rejected result: {}", store_err);
+            }
+        }
+        Err(e) => {
+            eprintln!("API submission failed, moving result to outbox: {}", e);
+            let payload = OutboxPayload::ScanResult { result: Box::new(result.clone()) };
+            if let Err(store_err) = storage::enqueue_outbox(payload, &e.message) {
+                eprintln!("[Outbox] Failed to persist result: {}", store_err);
+            }
+            emit_outbox_status(app);
+        }
+    }
+}
+
+/// Finalize a scan session. If some of its results are still in the outbox the finalize
+/// call is queued behind them, so the server never finalizes an incomplete session.
+pub async fn finalize_or_enqueue(app: &AppHandle, state: &Arc<AppState>, scan_session_id: &str, product_id: &str) {
+    let payload = OutboxPayload::FinalizeScan {
+        scan_session_id: scan_session_id.to_string(),
+        product_id: product_id.to_string(),
+    };
+
+    if storage::outbox_has_results_for_session(scan_session_id) {
+        eprintln!("[Outbox] Session {} has pending results, deferring finalize", scan_session_id);
+        if let Err(e) = storage::enqueue_outbox(payload, "Waiting for pending results") {
+            eprintln!("[Outbox] Failed to persist finalize: {}", e);
+        }
+        emit_outbox_status(app);
+        return;
+    }
+
+    eprintln!("Finalizing scan session {}...", scan_session_id);
+    let outcome = match crate::commands::auth::ensure_valid_token(state).await {
+        Ok(token) => post_finalize_scan(&token, scan_session_id, product_id).await,
+        Err(e) => Err(UploadError::retryable(format!("No valid auth token: {}", e))),
+    };
+
+    match outcome {
+        Ok(()) => eprintln!("Finalize scan successful"),
+        Err(e) if e.permanent => {
+            eprintln!("API rejected the finalize, setting it aside: {}", e);
+            if let Err(store_err) = storage::store_rejected_upload(payload, 1, &e.message) {
+                eprintln!("[Outbox] Failed to keep rejected finalize: {}", store_err);
+            }
+        }
+        Err(e) => {
+            eprintln!("Finalize scan failed, moving to outbox: {}", e);
+            if let Err(store_err) = storage::enqueue_outbox(payload, &e.message) {
+                eprintln!("[Outbox] Failed to persist finalize: {}", store_err);
+            }
+            emit_outbox_status(app);
+        }
+    }
+}
+
+/// Try to upload every due outbox entry, oldest first.
+/// With `force` set, entries are retried even if their backoff has not elapsed.
+pub async fn flush_outbox(app: &AppHandle, force: bool) {
+    let _flush_guard = FLUSH_LOCK.lock().await;
+
+    let entries = storage::load_outbox();
+    if entries.is_empty() {
+        return;
+    }
+
+    let state = match app.try_state::<Arc<AppState>>() {
+        Some(s) => s.inner().clone(),
+        None => return,
+    };
+
+    let token = match crate::commands::auth::ensure_valid_token(&state).await {
+        Ok(t) => t,
+        Err(e) => {
+            println!("[Outbox] {} uploads pending, but not authenticated: {}", entries.len(), e);
+            return;
+        }
+    };
+
+    println!("[Outbox] Flushing {} pending uploads (force={})", entries.len(), force);
+    let now = chrono::Utc::now().timestamp();
+    let mut uploaded = 0;
+
+    // Sessions that still have a result in the outbox after this pass; their finalize must wait
+    let mut pending_sessions: HashSet<String> = HashSet::new();
+
+    for entry in &entries {
+        let session_id = entry.payload.scan_session_id().to_string();
+
+        if !force && entry.next_attempt_at > now {
+            pending_sessions.insert(session_id);
+            continue;
+        }
+
+        let outcome = match &entry.payload {
+            OutboxPayload::ScanResult { result } => post_scan_result(&token, result).await,
+            OutboxPayload::FinalizeScan { scan_session_id, product_id } => {
+                if pending_sessions.contains(scan_session_id) {
+                    continue;
+                }
+                post_finalize_scan(&token, scan_session_id, product_id).await
+            }
+        };
+
+        match outcome {
+            Ok(()) => {
+                uploaded += 1;
+                if let Err(e) = storage::remove_outbox_entry(&entry.id) {
+                    eprintln!("[Outbox] Failed to remove uploaded entry {}: {}", entry.id, e);
+                }
+            }
+            // Given up on: it no longer holds back its session's finalize
+            Err(e) if e.permanent || entry.attempts + 1 >= MAX_OUTBOX_ATTEMPTS => {
+                eprintln!("[Outbox] Giving up on {} after {} attempts: {}", entry.id, entry.attempts + 1, e);
+                if let Err(store_err) = storage::reject_outbox_entry(&entry.id, &e.message) {
+                    eprintln!("[Outbox] Failed to set aside rejected entry: {}", store_err);
+                }
+            }
+            Err(e) => {
+                eprintln!("[Outbox] Upload of {} failed (attempt {}): {}", entry.id, entry.attempts + 1, e);
+                if let Err(store_err) = storage::record_outbox_failure(&entry.id, &e.message) {
+                    eprintln!("[Outbox] Failed to record failure: {}", store_err);
+                }
+                pending_sessions.insert(session_id);
+            }
+        }
+    }
+
+    println!("[Outbox] Flush complete, {} uploaded", uploaded);
+    emit_outbox_status(app);
+}
+
+/// Build a summary of what is waiting in the outbox
+pub fn outbox_status() -> OutboxStatus {
+    let entries = storage::load_outbox();
+
+    let pending_finalizations = entries
+        .iter()
+        .filter(|e| matches!(e.payload, OutboxPayload::FinalizeScan { .. }))
+        .count();
+
+    OutboxStatus {
+        pending_results: entries.len() - pending_finalizations,
+        pending_finalizations,
+        oldest_created_at: entries.iter().map(|e| e.created_at).min(),
+        next_attempt_at: entries.iter().map(|e| e.next_attempt_at).min(),
+        last_error: entries.iter().rev().find_map(|e| e.last_error.clone()),
+    }
+}
+
+fn emit_outbox_status(app: &AppHandle) {
+    let _ = app.emit("outbox:updated", outbox_status());
+}
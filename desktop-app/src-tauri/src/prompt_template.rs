@@ -0,0 +1,302 @@
+//! Per-country prompt text: translations and template variables
+//!
+//! For each country a prompt's translation is picked from `Prompt.translations` by the
+//! first key that matches: the country code ("at"), the country's main language with the
+//! country ("de-at"), then the language alone ("de"). Keys are matched case-insensitively;
+//! without a match the base text is used.
+//!
+//! Prompt texts and follow-ups (translated or not) may contain `{variable}` placeholders
+//! that are expanded per country when a scan runs:
+//!
+//! - `{brand}`: the product's brand
+//! - `{country}`: the country's name as set on its proxy (`StaticProxy.country_name`)
+//! - `{country_local}`: the country's name in its own main language, e.g. "Deutschland"
+//! - `{country_code}`: the lowercase country code, e.g. "de"
+//! - `{year}`, `{month}`: year and English month name the scan session started in
+//!
+//! The country variables aren't set in the "local" lane. Placeholders that aren't set
+//! are left in the text unchanged.
+
+use crate::{Prompt, PromptTranslation};
+use chrono::{DateTime, Datelike, Local};
+use std::collections::HashMap;
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Name of a country in its own main language
+fn localized_country_name(country_code: &str) -> Option<&'static str> {
+    let name = match country_code {
+        "us" => "United States",
+        "gb" | "uk" => "United Kingdom",
+        "ie" => "Ireland",
+        "ca" => "Canada",
+        "au" => "Australia",
+        "nz" => "New Zealand",
+        "za" => "South Africa",
+        "in" => "India",
+        "sg" => "Singapore",
+        "de" => "Deutschland",
+        "at" => "Österreich",
+        "ch" => "Schweiz",
+        "fr" => "France",
+        "be" => "België",
+        "nl" => "Nederland",
+        "lu" => "Luxembourg",
+        "es" => "España",
+        "pt" => "Portugal",
+        "it" => "Italia",
+        "pl" => "Polska",
+        "cz" => "Česko",
+        "sk" => "Slovensko",
+        "hu" => "Magyarország",
+        "ro" => "România",
+        "gr" => "Ελλάδα",
+        "se" => "Sverige",
+        "no" => "Norge",
+        "dk" => "Danmark",
+        "fi" => "Suomi",
+        "tr" => "Türkiye",
+        "ua" => "Україна",
+        "ru" => "Россия",
+        "il" => "ישראל",
+        "ae" => "الإمارات",
+        "sa" => "السعودية",
+        "br" => "Brasil",
+        "mx" => "México",
+        "ar" => "Argentina",
+        "cl" => "Chile",
+        "co" => "Colombia",
+        "jp" => "日本",
+        "kr" => "대한민국",
+        "cn" => "中国",
+        "tw" => "台灣",
+        "id" => "Indonesia",
+        "th" => "ประเทศไทย",
+        "vn" => "Việt Nam",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// ISO 639-1 code of a country's main language
+fn country_language(country_code: &str) -> Option<&'static str> {
+    let language = match country_code {
+        "us" | "gb" | "uk" | "ie" | "ca" | "au" | "nz" | "za" | "in" | "sg" => "en",
+        "de" | "at" | "ch" => "de",
+        "fr" | "lu" => "fr",
+        "nl" | "be" => "nl",
+        "es" | "mx" | "ar" | "cl" | "co" => "es",
+        "pt" | "br" => "pt",
+        "it" => "it",
+        "pl" => "pl",
+        "cz" => "cs",
+        "sk" => "sk",
+        "hu" => "hu",
+        "ro" => "ro",
+        "gr" => "el",
+        "se" => "sv",
+        "no" => "nb",
+        "dk" => "da",
+        "fi" => "fi",
+        "tr" => "tr",
+        "ua" => "uk",
+        "ru" => "ru",
+        "il" => "he",
+        "ae" | "sa" => "ar",
+        "jp" => "ja",
+        "kr" => "ko",
+        "cn" | "tw" => "zh",
+        "id" => "id",
+        "th" => "th",
+        "vn" => "vi",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// ISO 639-1 language codes, some of which are also country codes ("ar", "ca", "be", ...)
+const LANGUAGE_CODES: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// The prompt's translation for a country and its key, if it has one.
+/// "language-country" comes first, then a bare country key, then the country's language.
+/// A bare key that is also a language code ("ar" is Arabic as well as Argentina) only counts
+/// as the country if it is the country's own language.
+fn pick_translation<'a>(prompt: &'a Prompt, country_code: &str) -> Option<(&'a String, &'a PromptTranslation)> {
+    let country = country_code.to_lowercase();
+    let language = country_language(&country);
+    let country_key = (!LANGUAGE_CODES.contains(&country.as_str()) || language == Some(country.as_str()))
+        .then(|| country.clone());
+
+    let candidates = [
+        language.map(|l| format!("{}-{}", l, country)),
+        country_key,
+        language.map(str::to_string),
+    ];
+    candidates.into_iter().flatten().find_map(|candidate| {
+        prompt.translations.iter().find(|(key, _)| key.to_lowercase() == candidate)
+    })
+}
+
+/// Variables for one country of a scan session that started at `started_at`
+pub fn template_vars(brand: &str, country_code: &str, started_at: DateTime<Local>) -> HashMap<&'static str, String> {
+    let mut vars = HashMap::new();
+    vars.insert("brand", brand.to_string());
+    vars.insert("year", started_at.year().to_string());
+    vars.insert("month", MONTHS[started_at.month0() as usize].to_string());
+
+    if country_code != "local" {
+        let code = country_code.to_lowercase();
+        let localized = localized_country_name(&code);
+        let name = crate::storage::get_static_proxy(&code)
+            .and_then(|p| p.country_name)
+            .filter(|n| !n.is_empty())
+            .or_else(|| localized.map(str::to_string))
+            .unwrap_or_else(|| code.to_uppercase());
+
+        vars.insert("country_local", localized.map(str::to_string).unwrap_or_else(|| name.clone()));
+        vars.insert("country", name);
+        vars.insert("country_code", code);
+    }
+
+    vars
+}
+
+/// Replace every `{variable}` in `template` that has a value in `vars`
+pub fn render(template: &str, vars: &HashMap<&'static str, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| vars.get(&after[..end]).map(|v| (end, v)));
+        match value {
+            Some((end, value)) => {
+                rendered.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Copies of `prompts` for a country, translated and with text and follow-ups rendered,
+/// in the same order. `Prompt.variant` is set to the translation key used.
+pub fn render_prompts(prompts: &[Prompt], country_code: &str, vars: &HashMap<&'static str, String>) -> Vec<Prompt> {
+    prompts
+        .iter()
+        .map(|prompt| {
+            let translation = pick_translation(prompt, country_code);
+            let (text, follow_ups) = match translation {
+                Some((_, t)) if !t.follow_ups.is_empty() => (&t.text, &t.follow_ups),
+                Some((_, t)) => (&t.text, &prompt.follow_ups),
+                None => (&prompt.text, &prompt.follow_ups),
+            };
+
+            Prompt {
+                text: render(text, vars),
+                follow_ups: follow_ups.iter().map(|f| render(f, vars)).collect(),
+                variant: translation.map(|(key, _)| key.to_lowercase()),
+                ..prompt.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("brand", "Acme".to_string()),
+            ("country", "Germany".to_string()),
+            ("year", "2026".to_string()),
+        ])
+    }
+
+    fn prompt(translations: &[(&str, &str)]) -> Prompt {
+        Prompt {
+            id: "p1".to_string(),
+            text: "Best CRM in {country}?".to_string(),
+            category: None,
+            target_regions: Vec::new(),
+            follow_ups: vec!["Is {brand} one of them?".to_string()],
+            translations: translations.iter()
+                .map(|(key, text)| (key.to_string(), PromptTranslation { text: text.to_string(), follow_ups: Vec::new() }))
+                .collect(),
+            variant: None,
+        }
+    }
+
+    #[test]
+    fn render_expands_known_variables() {
+        assert_eq!(render("Is {brand} the best CRM in {country} in {year}?", &vars()), "Is Acme the best CRM in Germany in 2026?");
+        assert_eq!(render("{brand}{brand}", &vars()), "AcmeAcme");
+    }
+
+    #[test]
+    fn render_keeps_unknown_placeholders_and_stray_braces() {
+        assert_eq!(render("Compare {brand} and {competitor}", &vars()), "Compare Acme and {competitor}");
+        assert_eq!(render("JSON like {\"a\": 1} stays", &vars()), "JSON like {\"a\": 1} stays");
+        assert_eq!(render("Unclosed {brand", &vars()), "Unclosed {brand");
+        assert_eq!(render("Nested {{brand}}", &vars()), "Nested {Acme}");
+    }
+
+    #[test]
+    fn translation_prefers_language_country_then_country_then_language() {
+        let p = prompt(&[("de", "Bestes CRM?"), ("de-at", "Bestes CRM in AT?"), ("AT", "Servus CRM?")]);
+        assert_eq!(pick_translation(&p, "at").map(|(k, _)| k.as_str()), Some("de-at"));
+
+        let p = prompt(&[("de", "Bestes CRM?"), ("AT", "Servus CRM?")]);
+        assert_eq!(pick_translation(&p, "AT").map(|(k, _)| k.as_str()), Some("AT"));
+        assert_eq!(pick_translation(&p, "ch").map(|(k, _)| k.as_str()), Some("de"));
+        assert!(pick_translation(&p, "fr").is_none());
+        assert!(pick_translation(&p, "local").is_none());
+    }
+
+    #[test]
+    fn bare_keys_that_are_other_languages_are_not_country_keys() {
+        // "ar" is Arabic, not Argentina; "ca" Catalan, not Canada; "be" Belarusian, not Belgium
+        let p = prompt(&[("ar", "أفضل CRM؟"), ("es", "¿Mejor CRM?"), ("ca", "Millor CRM?"), ("be", "Лепшы CRM?")]);
+        assert_eq!(pick_translation(&p, "ar").map(|(k, _)| k.as_str()), Some("es"));
+        assert!(pick_translation(&p, "ca").is_none());
+        assert!(pick_translation(&p, "be").is_none());
+        assert_eq!(pick_translation(&p, "ae").map(|(k, _)| k.as_str()), Some("ar"));
+    }
+
+    #[test]
+    fn render_prompts_uses_translation_and_base_follow_ups() {
+        let p = prompt(&[("DE", "Bestes CRM in {country}?")]);
+        let rendered = render_prompts(&[p], "de", &vars());
+        assert_eq!(rendered[0].text, "Bestes CRM in Germany?");
+        assert_eq!(rendered[0].follow_ups, vec!["Is Acme one of them?"]);
+        assert_eq!(rendered[0].variant.as_deref(), Some("de"));
+
+        let rendered = render_prompts(&[prompt(&[])], "us", &vars());
+        assert_eq!(rendered[0].text, "Best CRM in Germany?");
+        assert_eq!(rendered[0].variant, None);
+    }
+}
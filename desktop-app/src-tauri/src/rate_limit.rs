@@ -0,0 +1,296 @@
+//! Prompt pacing per platform account
+//!
+//! Every country/platform pair is its own account (own cookies, own proxy), so limits are
+//! tracked per "{country_code}:{platform}" key with the platform's `RateLimitPolicy`: a
+//! minimum gap between prompts plus random jitter, and a cap per rolling hour. Prompt times
+//! are kept in `rate_limit_usage.json` so the hourly cap survives restarts.
+
+use crate::storage::{self, RateLimitPolicy};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tokio::time::Duration;
+
+/// Rolling window of the hourly cap
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+/// Longest single sleep while waiting, so cancellation is noticed quickly
+const WAIT_STEP_MS: i64 = 2000;
+
+lazy_static::lazy_static! {
+    /// Recent prompt times per account, loaded from disk on first use
+    static ref USAGE: Mutex<Option<HashMap<String, Vec<i64>>>> = Mutex::new(None);
+}
+
+/// Key of the account a lane uses; same format as the country/platform auth keys
+pub fn account_key(country_code: &str, platform: &str) -> String {
+    format!("{}:{}", country_code, platform)
+}
+
+/// Run `f` on the usage map with entries older than an hour dropped
+fn with_usage<T>(f: impl FnOnce(&mut HashMap<String, Vec<i64>>) -> T) -> T {
+    let mut usage = USAGE.lock();
+    let map = usage.get_or_insert_with(storage::load_rate_limit_usage);
+
+    let cutoff = chrono::Utc::now().timestamp_millis() - HOUR_MS;
+    map.values_mut().for_each(|times| times.retain(|t| *t > cutoff));
+    map.retain(|_, times| !times.is_empty());
+
+    f(map)
+}
+
+/// Random delay in `0..=jitter_secs` seconds, in milliseconds
+fn random_jitter_ms(jitter_secs: u64) -> i64 {
+    if jitter_secs == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() % (jitter_secs as u128 * 1000 + 1)) as i64
+}
+
+/// Earliest unix millisecond at which the account may send its next prompt
+fn next_allowed_at(times: &[i64], policy: &RateLimitPolicy, jitter_ms: i64) -> i64 {
+    let mut allowed = times
+        .iter()
+        .max()
+        .map(|last| last + policy.min_gap_secs as i64 * 1000 + jitter_ms)
+        .unwrap_or(0);
+
+    let cap = policy.max_per_hour as usize;
+    if cap > 0 && times.len() >= cap {
+        // Wait until enough prompts have left the window to get back under the cap
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable();
+        allowed = allowed.max(sorted[sorted.len() - cap] + HOUR_MS);
+    }
+
+    allowed
+}
+
+/// Wait until the account may send another prompt under its platform's policy, without
+/// claiming the turn; lets a lane hold off before it takes a webview slot. Jitter is left to
+/// `reserve_turn`. Returns false if `keep_waiting` turned false first.
+pub async fn wait_for_turn(country_code: &str, platform: &str, keep_waiting: impl Fn() -> bool) -> bool {
+    let key = account_key(country_code, platform);
+    let policy = storage::get_rate_limit(platform);
+
+    loop {
+        if !keep_waiting() {
+            return false;
+        }
+
+        let allowed_at = with_usage(|usage| {
+            next_allowed_at(usage.get(&key).map(Vec::as_slice).unwrap_or(&[]), &policy, 0)
+        });
+        let wait_ms = allowed_at - chrono::Utc::now().timestamp_millis();
+        if wait_ms <= 0 {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(wait_ms.min(WAIT_STEP_MS) as u64)).await;
+    }
+}
+
+/// Claim the account's next prompt slot if it is free: checks the policy and records the
+/// prompt in one step, so two tasks on the same account can't both take it.
+/// Returns 0 if the slot was taken, otherwise the milliseconds left to wait.
+fn try_reserve(key: &str, policy: &RateLimitPolicy, jitter_ms: i64) -> i64 {
+    with_usage(|usage| {
+        let now = chrono::Utc::now().timestamp_millis();
+        let allowed_at = next_allowed_at(usage.get(key).map(Vec::as_slice).unwrap_or(&[]), policy, jitter_ms);
+        if allowed_at > now {
+            return allowed_at - now;
+        }
+
+        usage.entry(key.to_string()).or_default().push(now);
+        if let Err(e) = storage::save_rate_limit_usage(usage) {
+            eprintln!("[RateLimit] Failed to persist usage: {}", e);
+        }
+        0
+    })
+}
+
+/// Wait until the account may send another prompt under its platform's policy and record
+/// it as sent; call right before submitting. Returns false (with nothing recorded) if
+/// `keep_waiting` turned false first (e.g. the scan was cancelled).
+pub async fn reserve_turn(country_code: &str, platform: &str, keep_waiting: impl Fn() -> bool) -> bool {
+    let key = account_key(country_code, platform);
+    let policy = storage::get_rate_limit(platform);
+    // Jitter is drawn once per prompt so repeated checks don't keep moving the target
+    let jitter_ms = random_jitter_ms(policy.jitter_secs);
+    let mut logged = false;
+
+    loop {
+        if !keep_waiting() {
+            return false;
+        }
+
+        let wait_ms = try_reserve(&key, &policy, jitter_ms);
+        if wait_ms <= 0 {
+            return true;
+        }
+
+        if !logged {
+            eprintln!("[RateLimit] {} waits {}s before its next prompt", key, wait_ms / 1000);
+            logged = true;
+        }
+        tokio::time::sleep(Duration::from_millis(wait_ms.min(WAIT_STEP_MS) as u64)).await;
+    }
+}
+
+/// Seconds in a duration unit word such as "minutes" or "hrs"
+fn unit_secs(word: &str) -> Option<i64> {
+    if word.starts_with("sec") {
+        Some(1)
+    } else if word.starts_with("min") {
+        Some(60)
+    } else if word.starts_with("hour") || word == "hr" || word == "hrs" {
+        Some(3600)
+    } else if word.starts_with("day") {
+        Some(86400)
+    } else {
+        None
+    }
+}
+
+fn amount(word: &str) -> Option<i64> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        _ => word.parse().ok(),
+    }
+}
+
+/// Parse "3:45", "15:00", "4pm" or "4:22pm" (with `meridiem` from the next word) into hour and minute
+fn clock_time(word: &str, next: Option<&str>) -> Option<(u32, u32)> {
+    let (digits, meridiem) = if let Some(d) = word.strip_suffix("pm") {
+        (d, Some("pm"))
+    } else if let Some(d) = word.strip_suffix("am") {
+        (d, Some("am"))
+    } else {
+        (word, next.filter(|n| *n == "am" || *n == "pm"))
+    };
+
+    let (hour, minute) = match digits.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm after it
+        None if meridiem.is_some() => (digits.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    if minute > 59 {
+        return None;
+    }
+
+    let hour = match meridiem {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some("pm") if hour < 12 => hour + 12,
+        Some("am") if hour == 12 => 0,
+        _ => hour,
+    };
+    (hour < 24).then_some((hour, minute))
+}
+
+/// Find when a usage limit lifts in a platform's notice, e.g. "try again in 2 hours",
+/// "resets in 1 hour and 30 minutes" or "try again after 4:22 PM" (local time).
+/// Returns unix seconds, or None if the notice doesn't say.
+pub fn parse_limit_reset(notice: &str, now: chrono::DateTime<chrono::Local>) -> Option<i64> {
+    let lower = notice.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | '!' | '?' | '(' | ')' | ';'))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // Relative: "in <amount> <unit> [and <amount> <unit>]"
+    for (i, word) in words.iter().enumerate() {
+        if *word != "in" {
+            continue;
+        }
+        let mut total = 0;
+        let mut j = i + 1;
+        while j + 1 < words.len() {
+            let (Some(n), Some(unit)) = (amount(words[j]), unit_secs(words[j + 1])) else {
+                break;
+            };
+            total += n * unit;
+            j += 2;
+            if words.get(j) == Some(&"and") {
+                j += 1;
+            }
+        }
+        if total > 0 {
+            return Some(now.timestamp() + total);
+        }
+    }
+
+    // Absolute: the first clock time, at its next occurrence
+    let tomorrow = words.contains(&"tomorrow");
+    for (i, word) in words.iter().enumerate() {
+        let Some((hour, minute)) = clock_time(word, words.get(i + 1).copied()) else {
+            continue;
+        };
+        let mut date = now.date_naive();
+        if tomorrow {
+            date = date.succ_opt()?;
+        }
+        let mut reset = date.and_hms_opt(hour, minute, 0)?.and_local_timezone(chrono::Local).earliest()?;
+        if reset <= now {
+            reset += chrono::Duration::days(1);
+        }
+        return Some(reset.timestamp());
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// 2026-06-10 14:00 local time, clear of DST changes
+    fn now() -> chrono::DateTime<chrono::Local> {
+        chrono::Local.with_ymd_and_hms(2026, 6, 10, 14, 0, 0).unwrap()
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> i64 {
+        chrono::Local.with_ymd_and_hms(2026, 6, day, hour, minute, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn relative_resets() {
+        let base = now().timestamp();
+        assert_eq!(parse_limit_reset("Please try again in 2 hours.", now()), Some(base + 2 * 3600));
+        assert_eq!(parse_limit_reset("Limit resets in 1 hour and 30 minutes", now()), Some(base + 5400));
+        assert_eq!(parse_limit_reset("Try again in an hour", now()), Some(base + 3600));
+        assert_eq!(parse_limit_reset("Try again in 45 secs", now()), Some(base + 45));
+    }
+
+    #[test]
+    fn clock_time_resets() {
+        assert_eq!(parse_limit_reset("You can try again after 4:22 PM.", now()), Some(local(10, 16, 22)));
+        assert_eq!(parse_limit_reset("Out of messages until 4pm", now()), Some(local(10, 16, 0)));
+        assert_eq!(parse_limit_reset("Resets at 15:00", now()), Some(local(10, 15, 0)));
+        // A time already past today means tomorrow
+        assert_eq!(parse_limit_reset("Resets at 9:30 am", now()), Some(local(11, 9, 30)));
+        assert_eq!(parse_limit_reset("Come back tomorrow at 3 pm", now()), Some(local(11, 15, 0)));
+    }
+
+    #[test]
+    fn relative_wins_over_clock_time() {
+        let base = now().timestamp();
+        assert_eq!(parse_limit_reset("At 4:00 PM the limit resets, in 2 hours", now()), Some(base + 7200));
+    }
+
+    #[test]
+    fn notices_without_a_reset_time() {
+        assert_eq!(parse_limit_reset("You've reached your limit.", now()), None);
+        // Numbers that aren't times or durations
+        assert_eq!(parse_limit_reset("Upgrade to get 5 times more messages in GPT-4", now()), None);
+        assert_eq!(parse_limit_reset("Resets at 25:00", now()), None);
+        assert_eq!(parse_limit_reset("Resets at 13pm", now()), None);
+    }
+
+    #[test]
+    fn hourly_cap_waits_for_the_oldest_prompt_in_the_window() {
+        let policy = RateLimitPolicy { min_gap_secs: 10, max_per_hour: 2, jitter_secs: 0 };
+        assert_eq!(next_allowed_at(&[], &policy, 0), 0);
+        assert_eq!(next_allowed_at(&[1_000], &policy, 500), 1_000 + 10_000 + 500);
+        assert_eq!(next_allowed_at(&[5_000, 1_000], &policy, 0), 1_000 + HOUR_MS);
+    }
+}
@@ -0,0 +1,322 @@
+//! Offline re-extraction of archived responses
+//!
+//! Re-runs brand-mention, competitor, list-position, sentiment, citation and
+//! credits-exhausted detection over a session in the local history with the current
+//! extraction rules and the product's current brand domains and competitors.
+//!
+//! - Results with a DOM snapshot are loaded into a hidden webview and run through the
+//!   current collect script, so selector fixes apply to old data as well.
+//! - Results without one fall back to the stored response text and citations. That covers
+//!   brand and competitor mentions and brand citation, but not credits exhaustion (which
+//!   needs the page) or citations the old selectors missed.
+//!
+//! Brand and competitor mentions are matched by `analysis` on the response text either way.
+//! - Multi-turn results always use the stored text of each turn, since the collect script
+//!   only reads the last answer of a chat.
+
+use crate::analysis::{self, competitors, BrandTerms, Competitor};
+use crate::commands::scan::{fetch_product_prompts, merge_turns};
+use crate::scan_history::{self, HistoryEntry, HistoryQuery};
+use crate::webview::WebviewManager;
+use crate::{storage, AppState, Citation, CompetitorDetailResult, ConversationTurn, ScanResult};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::io::Read;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+/// Brand and competitor settings the extraction runs against
+struct ExtractionRules {
+    brand: BrandTerms,
+    brand_domain: Option<String>,
+    domain_aliases: Option<Vec<String>>,
+    competitors: Vec<Competitor>,
+}
+
+/// What a result was re-extracted from
+#[derive(Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReanalysisSource {
+    Snapshot,
+    ResponseText,
+}
+
+/// A stored result whose extracted fields changed
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReanalyzedResult {
+    pub history_id: i64,
+    pub platform: String,
+    pub country: String,
+    pub prompt_id: String,
+    pub sample: usize,
+    pub source: ReanalysisSource,
+    /// Names of the `ScanResult` fields that differ from the stored result
+    pub changed_fields: Vec<String>,
+    pub result: ScanResult,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReanalysisSummary {
+    pub scan_session_id: String,
+    pub results: usize,
+    pub from_snapshots: usize,
+    pub from_response_text: usize,
+    pub changed: Vec<ReanalyzedResult>,
+    pub resubmitted: usize,
+}
+
+/// Re-extract every stored result of a session, update the local history with the
+/// corrected results and, with `resubmit` set, send the changed ones to the API again.
+/// They carry their `result_key`, so the API replaces the stored results instead of
+/// adding corrected copies next to them.
+pub async fn reanalyze_session(
+    app: &AppHandle,
+    state: &Arc<AppState>,
+    scan_session_id: &str,
+    resubmit: bool,
+) -> Result<ReanalysisSummary, String> {
+    let query = HistoryQuery {
+        scan_session_id: Some(scan_session_id.to_string()),
+        ..Default::default()
+    };
+    let mut entries = scan_history::query_results(&query)?;
+    entries.reverse();
+
+    let product_id = entries
+        .first()
+        .map(|e| e.result.product_id.clone())
+        .ok_or("No stored results for this scan session")?;
+    let rules = load_rules(state, &product_id, scan_session_id).await?;
+    let manager = WebviewManager::new();
+
+    println!("[Reanalysis] Re-extracting {} results of session {}", entries.len(), scan_session_id);
+
+    let mut summary = ReanalysisSummary {
+        scan_session_id: scan_session_id.to_string(),
+        results: entries.len(),
+        from_snapshots: 0,
+        from_response_text: 0,
+        changed: Vec::new(),
+        resubmitted: 0,
+    };
+
+    for entry in &entries {
+        let (corrected, source) = reextract_entry(app, &manager, entry, &rules).await;
+        match source {
+            ReanalysisSource::Snapshot => summary.from_snapshots += 1,
+            ReanalysisSource::ResponseText => summary.from_response_text += 1,
+        }
+
+        let changed_fields = changed_fields(&entry.result, &corrected);
+        if changed_fields.is_empty() {
+            continue;
+        }
+
+        println!("[Reanalysis] Result {} changed: {}", entry.id, changed_fields.join(", "));
+        scan_history::update_extraction(entry.id, &corrected)?;
+
+        if resubmit {
+            crate::outbox::submit_or_enqueue(app, state, &corrected).await;
+            summary.resubmitted += 1;
+        }
+
+        summary.changed.push(ReanalyzedResult {
+            history_id: entry.id,
+            platform: corrected.platform.clone(),
+            country: corrected.request_country.clone().unwrap_or_else(|| "local".to_string()),
+            prompt_id: corrected.prompt_id.clone(),
+            sample: entry.sample,
+            source,
+            changed_fields,
+            result: corrected,
+        });
+    }
+
+    // Have the server recompute the session's aggregates from the corrected results
+    if summary.resubmitted > 0 {
+        crate::outbox::finalize_or_enqueue(app, state, scan_session_id, &product_id).await;
+    }
+
+    println!("[Reanalysis] Session {}: {} of {} results changed ({} from snapshots)",
+        scan_session_id, summary.changed.len(), summary.results, summary.from_snapshots);
+
+    Ok(summary)
+}
+
+/// Current rules from the API, or the ones the session ran with if the API is unreachable
+async fn load_rules(state: &Arc<AppState>, product_id: &str, scan_session_id: &str) -> Result<ExtractionRules, String> {
+    match fetch_product_prompts(state, product_id).await {
+        Ok(response) => {
+            let product_config = storage::get_product_config(product_id);
+            Ok(ExtractionRules {
+                brand: BrandTerms::for_product(&response.product, &product_config),
+                brand_domain: response.product.domain,
+                domain_aliases: response.product.domain_aliases,
+                competitors: competitors::with_local_terms(response.competitors, &product_config.competitor_terms),
+            })
+        }
+        Err(e) => {
+            eprintln!("[Reanalysis] Could not fetch current product settings, using the session's: {}", e);
+            let checkpoint = storage::load_scan_checkpoint(scan_session_id)
+                .ok_or_else(|| format!("No brand settings available for this session: {}", e))?;
+            Ok(ExtractionRules {
+                brand: checkpoint.brand_terms(),
+                brand_domain: checkpoint.brand_domain,
+                domain_aliases: checkpoint.domain_aliases,
+                competitors: checkpoint.competitors,
+            })
+        }
+    }
+}
+
+/// Re-extract one result, from its snapshot if it has a readable one
+async fn reextract_entry(
+    app: &AppHandle,
+    manager: &WebviewManager,
+    entry: &HistoryEntry,
+    rules: &ExtractionRules,
+) -> (ScanResult, ReanalysisSource) {
+    if let (Some(path), true) = (&entry.snapshot_path, entry.result.turns.is_empty()) {
+        match reextract_from_snapshot(app, manager, entry, path, rules).await {
+            Ok(result) => return (result, ReanalysisSource::Snapshot),
+            Err(e) => eprintln!("[Reanalysis] Snapshot of result {} not usable, falling back to text: {}", entry.id, e),
+        }
+    }
+    (reextract_from_text(&entry.result, rules), ReanalysisSource::ResponseText)
+}
+
+async fn reextract_from_snapshot(
+    app: &AppHandle,
+    manager: &WebviewManager,
+    entry: &HistoryEntry,
+    path: &str,
+    rules: &ExtractionRules,
+) -> Result<ScanResult, String> {
+    let gzipped = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut html = String::new();
+    GzDecoder::new(&gzipped[..])
+        .read_to_string(&mut html)
+        .map_err(|e| format!("Failed to decompress {}: {}", path, e))?;
+
+    let label = format!("reanalyze-{}", entry.id);
+    let response = manager
+        .reextract_snapshot(
+            app,
+            &label,
+            &entry.result.platform,
+            &html,
+            entry.result.chat_url.as_deref(),
+            &rules.brand,
+            rules.brand_domain.as_deref(),
+            rules.domain_aliases.as_deref(),
+            &rules.competitors,
+        )
+        .await?;
+
+    let mut result = entry.result.clone();
+    result.response_text = response.response_text;
+    result.brand_mentioned = response.brand_mentioned;
+    result.brand_spans = response.brand_spans;
+    result.position = response.position;
+    result.sentiment = response.sentiment;
+    result.sentiment_score = response.sentiment_score;
+    result.citation_present = response.citation_present;
+    result.competitor_mentions = response.competitor_mentions;
+    result.competitor_details = response.competitor_details;
+    result.citations = response.citations;
+    // Limit banners sit outside the response area, so only a full-page snapshot can clear the flag
+    let full_page = html.starts_with("<!DOCTYPE html>");
+    result.credits_exhausted = response.credits_exhausted || (!full_page && entry.result.credits_exhausted);
+    Ok(result)
+}
+
+/// Apply the text-level rules of the collect script to a stored result, turn by turn for
+/// a multi-turn result
+fn reextract_from_text(stored: &ScanResult, rules: &ExtractionRules) -> ScanResult {
+    let mut result = stored.clone();
+
+    let found = analysis::analyze(&stored.response_text, &rules.brand, &rules.competitors);
+    result.brand_mentioned = found.brand_mentioned();
+    result.competitor_mentions = found.competitor_mentions();
+    result.brand_spans = found.brand_spans;
+    result.position = found.position;
+    result.sentiment = found.sentiment;
+    result.sentiment_score = found.sentiment_score;
+    result.competitor_details = found.competitor_details;
+
+    result.citation_present = cites_brand(&stored.citations, rules);
+
+    if !stored.turns.is_empty() {
+        let turns = stored.turns.iter()
+            .map(|turn| {
+                let found = analysis::analyze(&turn.response_text, &rules.brand, &rules.competitors);
+                ConversationTurn {
+                    brand_mentioned: found.brand_mentioned(),
+                    competitor_mentions: found.competitor_mentions(),
+                    brand_spans: found.brand_spans,
+                    position: found.position,
+                    sentiment_score: found.sentiment_score,
+                    citation_present: cites_brand(&turn.citations, rules),
+                    competitor_details: found.competitor_details,
+                    ..turn.clone()
+                }
+            })
+            .collect();
+        // Competitors may come up in any turn, so keep the stored details of those not in the first answer
+        let later: Vec<CompetitorDetailResult> = stored.competitor_details.iter()
+            .filter(|s| !result.competitor_details.iter().any(|cd| cd.name == s.name))
+            .map(|s| CompetitorDetailResult { spans: Vec::new(), ..s.clone() })
+            .collect();
+        result.competitor_details.extend(later);
+        merge_turns(&mut result, turns);
+    }
+
+    result
+}
+
+/// True if a citation points at the brand's domain or one of its aliases
+fn cites_brand(citations: &[Citation], rules: &ExtractionRules) -> bool {
+    let brand_domains: Vec<String> = rules.brand_domain.iter()
+        .chain(rules.domain_aliases.iter().flatten())
+        .filter(|d| !d.is_empty())
+        .map(|d| d.to_lowercase().replacen("www.", "", 1))
+        .collect();
+    citations.iter().any(|c| {
+        let Some(host) = tauri::Url::parse(&c.url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+            return false;
+        };
+        let host = host.replacen("www.", "", 1);
+        brand_domains.iter().any(|d| host.contains(d.as_str()) || d.contains(host.as_str()))
+    })
+}
+
+/// Extracted fields that differ between the stored and the re-extracted result
+fn changed_fields(stored: &ScanResult, corrected: &ScanResult) -> Vec<String> {
+    let citation_urls = |r: &ScanResult| r.citations.iter().map(|c| c.url.clone()).collect::<Vec<_>>();
+    let competitor_matches = |r: &ScanResult| r.competitor_details.iter()
+        .map(|cd| (cd.name.clone(), cd.position, cd.sentiment_score, cd.matched_aliases.clone(), cd.mentions, cd.spans.clone()))
+        .collect::<Vec<_>>();
+    let turn_fields = |r: &ScanResult| r.turns.iter()
+        .map(|t| (t.brand_mentioned, t.citation_present, t.competitor_mentions.clone()))
+        .collect::<Vec<_>>();
+
+    [
+        ("responseText", stored.response_text != corrected.response_text),
+        ("brandMentioned", stored.brand_mentioned != corrected.brand_mentioned),
+        ("brandSpans", stored.brand_spans != corrected.brand_spans),
+        ("position", stored.position != corrected.position),
+        ("sentiment", stored.sentiment != corrected.sentiment || stored.sentiment_score != corrected.sentiment_score),
+        ("citationPresent", stored.citation_present != corrected.citation_present),
+        ("competitorMentions", stored.competitor_mentions != corrected.competitor_mentions),
+        ("competitorDetails", competitor_matches(stored) != competitor_matches(corrected)),
+        ("citations", citation_urls(stored) != citation_urls(corrected)),
+        ("creditsExhausted", stored.credits_exhausted != corrected.credits_exhausted),
+        ("turns", turn_fields(stored) != turn_fields(corrected)),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
@@ -0,0 +1,345 @@
+//! Local scan history
+//!
+//! Every collected `ScanResult` is also written to `history.sqlite` in the config
+//! directory, together with its country, sample index and timings, so answers can be
+//! looked at and compared offline without the web dashboard.
+
+use crate::{storage, Citation, CompetitorDetailResult, ConversationTurn, ScanResult, TextSpan};
+use parking_lot::Mutex;
+use rusqlite::{params, params_from_iter, types::Value, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+lazy_static::lazy_static! {
+    /// Opened on first use; all access goes through this one connection
+    static ref HISTORY_DB: Mutex<Option<Connection>> = Mutex::new(None);
+}
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many have run.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE scan_results (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        scan_session_id TEXT NOT NULL,
+        product_id TEXT NOT NULL,
+        prompt_id TEXT NOT NULL,
+        prompt_text TEXT NOT NULL,
+        platform TEXT NOT NULL,
+        country TEXT NOT NULL,
+        sample INTEGER NOT NULL,
+        response_text TEXT NOT NULL,
+        brand_mentioned INTEGER NOT NULL,
+        citation_present INTEGER NOT NULL,
+        position INTEGER,
+        sentiment TEXT NOT NULL,
+        competitor_mentions TEXT NOT NULL,
+        competitor_details TEXT NOT NULL,
+        citations TEXT NOT NULL,
+        credits_exhausted INTEGER NOT NULL,
+        chat_url TEXT,
+        submitted_at INTEGER NOT NULL,
+        collected_at INTEGER NOT NULL,
+        response_wait_ms INTEGER,
+        response_timed_out INTEGER NOT NULL,
+        snapshot_path TEXT,
+        screenshot_path TEXT,
+        screenshot_object_path TEXT,
+        turns TEXT,
+        prompt_variant TEXT,
+        brand_spans TEXT,
+        sentiment_score REAL
+    );
+    CREATE INDEX idx_scan_results_product ON scan_results(product_id, collected_at);
+    CREATE INDEX idx_scan_results_session ON scan_results(scan_session_id);",
+];
+
+/// A stored result with the local-only fields
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub sample: usize,
+    /// Unix milliseconds when the prompt was submitted
+    pub submitted_at: i64,
+    /// Unix milliseconds when the answer was collected
+    pub collected_at: i64,
+    /// Gzip-compressed HTML snapshot of the page, if snapshots were enabled
+    pub snapshot_path: Option<String>,
+    /// PNG screenshot of the answer, if screenshots were enabled
+    pub screenshot_path: Option<String>,
+    #[serde(flatten)]
+    pub result: ScanResult,
+}
+
+/// Filters for `query_results`. Unset fields don't restrict; dates are unix seconds.
+#[derive(Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryQuery {
+    pub product_id: Option<String>,
+    pub scan_session_id: Option<String>,
+    pub prompt_id: Option<String>,
+    pub platform: Option<String>,
+    pub country: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// One scan session as seen in the local history (times in unix milliseconds)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySession {
+    pub scan_session_id: String,
+    pub product_id: String,
+    pub first_collected_at: i64,
+    pub last_collected_at: i64,
+    pub results: usize,
+    pub brand_mentions: usize,
+    pub citations: usize,
+}
+
+/// Run `f` with the history database, opening and migrating it on first use
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut db = HISTORY_DB.lock();
+    if db.is_none() {
+        *db = Some(open_db()?);
+    }
+    let conn = db.as_ref().ok_or("History database not available")?;
+    f(conn).map_err(|e| format!("History database error: {}", e))
+}
+
+fn open_db() -> Result<Connection, String> {
+    let path = storage::get_history_db_path();
+    let conn = Connection::open(&path)
+        .map_err(|e| format!("Failed to open history database {:?}: {}", path, e))?;
+
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read history schema version: {}", e))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        println!("[History] Applying schema migration {}", index + 1);
+        conn.execute_batch(migration)
+            .and_then(|_| conn.pragma_update(None, "user_version", index + 1))
+            .map_err(|e| format!("History migration {} failed: {}", index + 1, e))?;
+    }
+
+    Ok(conn)
+}
+
+/// Store a collected result
+pub fn record_result(
+    result: &ScanResult,
+    sample: usize,
+    submitted_at: i64,
+    collected_at: i64,
+    snapshot_path: Option<&str>,
+    screenshot_path: Option<&str>,
+) -> Result<(), String> {
+    let competitor_mentions = serde_json::to_string(&result.competitor_mentions).unwrap_or_else(|_| "[]".to_string());
+    let competitor_details = serde_json::to_string(&result.competitor_details).unwrap_or_else(|_| "[]".to_string());
+    let citations = serde_json::to_string(&result.citations).unwrap_or_else(|_| "[]".to_string());
+    let turns = serde_json::to_string(&result.turns).unwrap_or_else(|_| "[]".to_string());
+    let brand_spans = serde_json::to_string(&result.brand_spans).unwrap_or_else(|_| "[]".to_string());
+
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO scan_results (
+                scan_session_id, product_id, prompt_id, prompt_text, platform, country, sample,
+                response_text, brand_mentioned, citation_present, position, sentiment,
+                competitor_mentions, competitor_details, citations, credits_exhausted, chat_url,
+                submitted_at, collected_at, response_wait_ms, response_timed_out, snapshot_path,
+                screenshot_path, screenshot_object_path, turns, prompt_variant, brand_spans, sentiment_score
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22,
+                      ?23, ?24, ?25, ?26, ?27, ?28)",
+            params![
+                result.scan_session_id,
+                result.product_id,
+                result.prompt_id,
+                result.prompt_text,
+                result.platform,
+                result.request_country.as_deref().unwrap_or("local"),
+                sample as i64,
+                result.response_text,
+                result.brand_mentioned,
+                result.citation_present,
+                result.position,
+                result.sentiment,
+                competitor_mentions,
+                competitor_details,
+                citations,
+                result.credits_exhausted,
+                result.chat_url,
+                submitted_at,
+                collected_at,
+                result.response_wait_ms.map(|ms| ms as i64),
+                result.response_timed_out,
+                snapshot_path,
+                screenshot_path,
+                result.screenshot_object_path,
+                turns,
+                result.prompt_variant,
+                brand_spans,
+                result.sentiment_score,
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+/// Overwrite the extracted fields of a stored result, e.g. after re-extraction
+pub fn update_extraction(id: i64, result: &ScanResult) -> Result<(), String> {
+    let competitor_mentions = serde_json::to_string(&result.competitor_mentions).unwrap_or_else(|_| "[]".to_string());
+    let competitor_details = serde_json::to_string(&result.competitor_details).unwrap_or_else(|_| "[]".to_string());
+    let citations = serde_json::to_string(&result.citations).unwrap_or_else(|_| "[]".to_string());
+    let turns = serde_json::to_string(&result.turns).unwrap_or_else(|_| "[]".to_string());
+    let brand_spans = serde_json::to_string(&result.brand_spans).unwrap_or_else(|_| "[]".to_string());
+
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE scan_results SET
+                response_text = ?2, brand_mentioned = ?3, citation_present = ?4,
+                competitor_mentions = ?5, competitor_details = ?6, citations = ?7, credits_exhausted = ?8,
+                turns = ?9, brand_spans = ?10, position = ?11, sentiment = ?12, sentiment_score = ?13
+             WHERE id = ?1",
+            params![
+                id,
+                result.response_text,
+                result.brand_mentioned,
+                result.citation_present,
+                competitor_mentions,
+                competitor_details,
+                citations,
+                result.credits_exhausted,
+                turns,
+                brand_spans,
+                result.position,
+                result.sentiment,
+                result.sentiment_score,
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+/// Query stored results, newest first
+pub fn query_results(query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    let text_filters = [
+        ("product_id = ?", &query.product_id),
+        ("scan_session_id = ?", &query.scan_session_id),
+        ("prompt_id = ?", &query.prompt_id),
+        ("platform = ?", &query.platform),
+        ("country = ?", &query.country),
+    ];
+    for (condition, value) in text_filters {
+        if let Some(v) = value {
+            conditions.push(condition);
+            values.push(Value::Text(v.clone()));
+        }
+    }
+    if let Some(from) = query.from {
+        conditions.push("collected_at >= ?");
+        values.push(Value::Integer(from * 1000));
+    }
+    if let Some(to) = query.to {
+        conditions.push("collected_at <= ?");
+        values.push(Value::Integer(to * 1000));
+    }
+
+    let mut sql = "SELECT * FROM scan_results".to_string();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY collected_at DESC, id DESC LIMIT ? OFFSET ?");
+    // SQLite treats a negative limit as "no limit"
+    values.push(Value::Integer(query.limit.map(|l| l as i64).unwrap_or(-1)));
+    values.push(Value::Integer(query.offset.unwrap_or(0) as i64));
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), entry_from_row)?;
+        rows.collect()
+    })
+}
+
+/// Sessions stored locally, newest first
+pub fn list_sessions(product_id: Option<&str>) -> Result<Vec<HistorySession>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT scan_session_id, product_id, MIN(collected_at), MAX(collected_at), COUNT(*),
+                    SUM(brand_mentioned), SUM(citation_present)
+             FROM scan_results
+             WHERE ?1 IS NULL OR product_id = ?1
+             GROUP BY scan_session_id
+             ORDER BY MAX(collected_at) DESC",
+        )?;
+        let rows = stmt.query_map(params![product_id], |row| {
+            Ok(HistorySession {
+                scan_session_id: row.get(0)?,
+                product_id: row.get(1)?,
+                first_collected_at: row.get(2)?,
+                last_collected_at: row.get(3)?,
+                results: row.get::<_, i64>(4)? as usize,
+                brand_mentions: row.get::<_, i64>(5)? as usize,
+                citations: row.get::<_, i64>(6)? as usize,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let competitor_mentions: String = row.get("competitor_mentions")?;
+    let competitor_details: String = row.get("competitor_details")?;
+    let citations: String = row.get("citations")?;
+    let turns: Option<String> = row.get("turns")?;
+    let brand_spans: Option<String> = row.get("brand_spans")?;
+    let country: String = row.get("country")?;
+    let scan_session_id: String = row.get("scan_session_id")?;
+    let platform: String = row.get("platform")?;
+    let prompt_id: String = row.get("prompt_id")?;
+    let sample = row.get::<_, i64>("sample")? as usize;
+    let result_key = crate::result_key(&scan_session_id, &country, &platform, &prompt_id, sample);
+
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        sample,
+        submitted_at: row.get("submitted_at")?,
+        collected_at: row.get("collected_at")?,
+        snapshot_path: row.get("snapshot_path")?,
+        screenshot_path: row.get("screenshot_path")?,
+        result: ScanResult {
+            product_id: row.get("product_id")?,
+            scan_session_id,
+            platform,
+            prompt_id,
+            prompt_text: row.get("prompt_text")?,
+            response_text: row.get("response_text")?,
+            brand_mentioned: row.get("brand_mentioned")?,
+            brand_spans: brand_spans
+                .and_then(|s| serde_json::from_str::<Vec<TextSpan>>(&s).ok())
+                .unwrap_or_default(),
+            citation_present: row.get("citation_present")?,
+            position: row.get("position")?,
+            sentiment: row.get("sentiment")?,
+            sentiment_score: row.get("sentiment_score")?,
+            competitor_mentions: serde_json::from_str(&competitor_mentions).unwrap_or_default(),
+            competitor_details: serde_json::from_str::<Vec<CompetitorDetailResult>>(&competitor_details).unwrap_or_default(),
+            citations: serde_json::from_str::<Vec<Citation>>(&citations).unwrap_or_default(),
+            credits_exhausted: row.get("credits_exhausted")?,
+            chat_url: row.get("chat_url")?,
+            request_country: Some(country),
+            response_wait_ms: row.get::<_, Option<i64>>("response_wait_ms")?.map(|ms| ms as u64),
+            response_timed_out: row.get("response_timed_out")?,
+            screenshot_object_path: row.get("screenshot_object_path")?,
+            turns: turns
+                .and_then(|t| serde_json::from_str::<Vec<ConversationTurn>>(&t).ok())
+                .unwrap_or_default(),
+            prompt_variant: row.get("prompt_variant")?,
+            result_key: Some(result_key),
+        },
+    })
+}
@@ -0,0 +1,232 @@
+//! Persistent scan queue
+//!
+//! Manual, scheduled and limit-deferred scans are all added to `scan_queue.json` in the
+//! config directory. A runner task starts the next job whenever no scan is running, so
+//! several products can be scanned back-to-back instead of failing with
+//! "Scan already in progress".
+
+use crate::commands::scan::{resume_scan_internal, start_scan_internal};
+use crate::storage::{self, ScanJob, ScanJobSource, ScanJobStatus};
+use crate::{AppState, PromptFilter};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use tokio::time::Duration;
+
+lazy_static::lazy_static! {
+    /// Wakes the runner when a job is added
+    static ref QUEUE_WAKE: Notify = Notify::new();
+    /// Job the runner has started and is waiting on
+    static ref CURRENT_JOB: parking_lot::Mutex<Option<ScanJob>> = parking_lot::Mutex::new(None);
+}
+
+/// Queue snapshot, returned by `get_scan_queue` and emitted as `scan:queue`
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanQueueStatus {
+    pub current: Option<ScanJob>,
+    /// Waiting jobs in execution order
+    pub jobs: Vec<ScanJob>,
+}
+
+/// Manual scans jump ahead of scheduled and re-queued ones unless a priority is given
+pub fn default_priority(source: ScanJobSource) -> i32 {
+    match source {
+        ScanJobSource::Manual => 10,
+        ScanJobSource::Scheduled | ScanJobSource::Requeued => 0,
+    }
+}
+
+/// Add a scan to the queue and wake the runner
+pub fn enqueue(
+    app: &AppHandle,
+    product_id: String,
+    platforms: Vec<String>,
+    samples: usize,
+    prompt_filter: PromptFilter,
+    priority: Option<i32>,
+    source: ScanJobSource,
+) -> Result<ScanJob, String> {
+    push_job(app, ScanJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        product_id,
+        platforms,
+        samples: samples.max(1),
+        prompt_filter,
+        priority: priority.unwrap_or_else(|| default_priority(source)),
+        source,
+        enqueued_at: chrono::Utc::now().timestamp(),
+        not_before: None,
+        limit_requeues: 0,
+        status: ScanJobStatus::Queued,
+        scan_session_id: None,
+    })
+}
+
+/// Queue the prompts a usage limit cut off on one country/platform account, to start once
+/// the limit has reset (`not_before`, unix seconds). `limit_requeues` counts how often in a
+/// row this has happened without the platform showing a reset time.
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_deferred(
+    app: &AppHandle,
+    product_id: String,
+    platform: String,
+    country_code: String,
+    prompt_ids: Vec<String>,
+    samples: usize,
+    not_before: i64,
+    limit_requeues: u32,
+) -> Result<ScanJob, String> {
+    let prompt_filter = PromptFilter {
+        prompt_ids,
+        countries: vec![country_code],
+        ..Default::default()
+    };
+
+    push_job(app, ScanJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        product_id,
+        platforms: vec![platform],
+        samples: samples.max(1),
+        prompt_filter,
+        priority: default_priority(ScanJobSource::Requeued),
+        source: ScanJobSource::Requeued,
+        enqueued_at: chrono::Utc::now().timestamp(),
+        not_before: Some(not_before),
+        limit_requeues,
+        status: ScanJobStatus::Queued,
+        scan_session_id: None,
+    })
+}
+
+fn push_job(app: &AppHandle, job: ScanJob) -> Result<ScanJob, String> {
+    println!("[ScanQueue] Enqueued {:?} scan {} for product {} (priority {}, not before {:?})",
+        job.source, job.id, job.product_id, job.priority, job.not_before);
+
+    storage::push_scan_job(job.clone())?;
+    emit_queue_status(app);
+    QUEUE_WAKE.notify_one();
+    Ok(job)
+}
+
+/// Current queue contents
+pub fn queue_status() -> ScanQueueStatus {
+    ScanQueueStatus {
+        current: CURRENT_JOB.lock().clone(),
+        jobs: storage::load_scan_queue(),
+    }
+}
+
+/// Job the runner is currently waiting on, if the running scan came from the queue
+pub fn current_job() -> Option<ScanJob> {
+    CURRENT_JOB.lock().clone()
+}
+
+pub fn emit_queue_status(app: &AppHandle) {
+    let _ = app.emit("scan:queue", queue_status());
+}
+
+/// Start the background runner that works through the queue
+pub fn start_queue_runner(app: AppHandle) {
+    // A job still marked running was cut off by a crash or quit; pick its session back up
+    match storage::requeue_running_scan_jobs() {
+        Ok(0) => {}
+        Ok(n) => println!("[ScanQueue] Re-queued {} scan(s) interrupted by the last shutdown", n),
+        Err(e) => eprintln!("[ScanQueue] Failed to re-queue interrupted scans: {}", e),
+    }
+
+    async_runtime::spawn(async move {
+        loop {
+            run_queued_jobs(&app).await;
+
+            // Re-check periodically as well: a scan started outside the queue (resume) blocks it
+            tokio::select! {
+                _ = QUEUE_WAKE.notified() => {}
+                _ = tokio::time::sleep(Duration::from_secs(10)) => {}
+            }
+        }
+    });
+}
+
+/// Run queued jobs one after another until the queue is empty or another scan is running
+async fn run_queued_jobs(app: &AppHandle) {
+    let state = match app.try_state::<Arc<AppState>>() {
+        Some(s) => s.inner().clone(),
+        None => return,
+    };
+
+    loop {
+        if state.scan.lock().is_running {
+            return;
+        }
+
+        let Some(mut job) = storage::take_next_scan_job() else {
+            return;
+        };
+
+        // A job cut off by a crash continues its session, so collected cells aren't sent twice
+        let checkpoint = job.scan_session_id.as_deref().and_then(storage::load_scan_checkpoint);
+        if let Some(checkpoint) = checkpoint.as_ref().filter(|c| c.status != "running") {
+            // It ended, or the user paused or cancelled it, before the job was removed
+            println!("[ScanQueue] Scan {} already ended as {}; not running it again", job.id, checkpoint.status);
+            finish_job(app, &job.id);
+            continue;
+        }
+
+        println!("[ScanQueue] Starting {:?} scan {} for product {}", job.source, job.id, job.product_id);
+        *CURRENT_JOB.lock() = Some(job.clone());
+        emit_queue_status(app);
+
+        let started = match checkpoint {
+            Some(checkpoint) => resume_scan_internal(checkpoint, app.clone(), state.clone()).await,
+            None => start_scan_internal(
+                job.product_id.clone(),
+                Some(job.samples),
+                Some(job.platforms.clone()),
+                job.prompt_filter.clone(),
+                app.clone(),
+                state.clone(),
+            ).await,
+        };
+
+        match started {
+            Ok(scan_session_id) => {
+                if let Err(e) = storage::set_scan_job_session(&job.id, &scan_session_id) {
+                    eprintln!("[ScanQueue] Failed to record the session of scan {}: {}", job.id, e);
+                }
+                job.scan_session_id = Some(scan_session_id);
+                *CURRENT_JOB.lock() = Some(job.clone());
+
+                // Wait for the scan to finish before starting the next job
+                while state.scan.lock().is_running {
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                }
+                println!("[ScanQueue] Scan {} finished", job.id);
+            }
+            Err(e) => {
+                eprintln!("[ScanQueue] Scan {} for product {} could not start: {}", job.id, job.product_id, e);
+                crate::webhooks::notify("scan:error", None, serde_json::json!({ "error": e, "productId": job.product_id }));
+                crate::notifications::notify(
+                    app,
+                    crate::notifications::NotificationKind::ScanFailed,
+                    &job.id,
+                    "Queued scan could not start",
+                    &e,
+                );
+                let _ = app.emit("scan:error", e);
+            }
+        }
+
+        finish_job(app, &job.id);
+    }
+}
+
+/// Remove a job that has run from the queue
+fn finish_job(app: &AppHandle, job_id: &str) {
+    if let Err(e) = storage::finish_scan_job(job_id) {
+        eprintln!("[ScanQueue] Failed to remove finished scan {} from the queue: {}", job_id, e);
+    }
+    *CURRENT_JOB.lock() = None;
+    emit_queue_status(app);
+}
@@ -2,9 +2,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::AuthState;
+use crate::analysis::{BrandTerms, Competitor};
+use crate::{AuthState, PromptFilter, ScanResult};
 
 /// Write to a debug log file for troubleshooting
 fn debug_log(msg: &str) {
@@ -59,6 +60,15 @@ pub struct PersistedState {
     /// Whether onboarding (initial credential setup) has been completed
     #[serde(default)]
     pub onboarding_completed: bool,
+    /// Rate-limit policies overriding the built-in defaults, keyed by platform
+    #[serde(default)]
+    pub rate_limits: HashMap<String, RateLimitPolicy>,
+    /// Endpoints that receive scan lifecycle events
+    #[serde(default)]
+    pub webhooks: Vec<WebhookEndpoint>,
+    /// Which events show a desktop notification
+    #[serde(default)]
+    pub notifications: NotificationSettings,
 }
 
 /// Proxy configuration from the API - DEPRECATED (use StaticProxy instead)
@@ -185,10 +195,51 @@ pub struct ProductConfig {
     /// Countries to scan this product in (empty = user's actual location, no proxy)
     #[serde(default)]
     pub scan_countries: Vec<String>,
+    /// Maximum number of scan webviews open at the same time
+    #[serde(default = "default_max_concurrent_webviews")]
+    pub max_concurrent_webviews: u32,
+    /// Whether to archive an HTML snapshot of every collected response
+    #[serde(default)]
+    pub snapshot_mode: SnapshotMode,
+    /// Upload archived snapshots to the server after each cell
+    #[serde(default)]
+    pub upload_snapshots: bool,
+    /// Whether to keep a PNG render of every collected answer as evidence. Best effort and
+    /// Windows-only: it is drawn from the answer's DOM in the page, not captured from the
+    /// screen, so it approximates styling, and the macOS and Linux webviews can't export it
+    #[serde(default)]
+    pub capture_screenshots: bool,
+    /// Upload screenshots and reference them from the submitted scan result
+    #[serde(default)]
+    pub upload_screenshots: bool,
+    /// Brand aliases used in addition to the product's
+    #[serde(default)]
+    pub brand_aliases: Vec<String>,
+    /// Brand exclusions used in addition to the product's
+    #[serde(default)]
+    pub brand_exclusions: Vec<String>,
+    /// Match the brand and its aliases only in the case they are written in
+    #[serde(default)]
+    pub brand_case_sensitive: bool,
+    /// Aliases, products, domains and negative keywords added to the product's
+    /// competitors of the same name
+    #[serde(default)]
+    pub competitor_terms: Vec<Competitor>,
+}
+
+/// What part of the page to archive for each collected response
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotMode {
+    #[default]
+    Off,
+    ResponseArea,
+    FullPage,
 }
 
 fn default_start_hour() -> u32 { 9 }
 fn default_end_hour() -> u32 { 17 }
+fn default_max_concurrent_webviews() -> u32 { 4 }
 
 impl Default for ProductConfig {
     fn default() -> Self {
@@ -203,6 +254,15 @@ impl Default for ProductConfig {
             scans_today: 0,
             scheduled_times: Vec::new(),
             scan_countries: Vec::new(),
+            max_concurrent_webviews: default_max_concurrent_webviews(),
+            snapshot_mode: SnapshotMode::Off,
+            upload_snapshots: false,
+            capture_screenshots: false,
+            upload_screenshots: false,
+            brand_aliases: Vec::new(),
+            brand_exclusions: Vec::new(),
+            brand_case_sensitive: false,
+            competitor_terms: Vec::new(),
         }
     }
 }
@@ -893,3 +953,962 @@ pub fn clear_authentication_tracking() -> Result<(), String> {
     state.platforms_last_authenticated_hash = None;
     save_state(&state)
 }
+
+// ============== Upload Outbox ==============
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles on the outbox file (scan task and uploader both touch it)
+    static ref OUTBOX_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+}
+
+/// Upload that the API has not accepted yet
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub payload: OutboxPayload,
+    /// Number of failed upload attempts so far
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    /// Earliest time (unix seconds) the uploader should try again
+    pub next_attempt_at: i64,
+}
+
+/// What to send once the API is reachable again
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutboxPayload {
+    ScanResult { result: Box<ScanResult> },
+    FinalizeScan { scan_session_id: String, product_id: String },
+}
+
+impl OutboxPayload {
+    pub fn scan_session_id(&self) -> &str {
+        match self {
+            OutboxPayload::ScanResult { result } => &result.scan_session_id,
+            OutboxPayload::FinalizeScan { scan_session_id, .. } => scan_session_id,
+        }
+    }
+}
+
+/// Get the path to the outbox file (kept separate from state.json since it holds full responses)
+fn get_outbox_path() -> PathBuf {
+    // get_config_path() makes sure the config directory exists
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("outbox.json"))
+        .unwrap_or_else(|| get_config_dir().join("outbox.json"))
+}
+
+/// Move a file that failed to parse out of the way as `<name>.corrupt-<unix secs>`, so the
+/// next write starts fresh without destroying what was in it
+fn set_aside_corrupt_file(path: &Path) {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".corrupt-{}", chrono::Utc::now().timestamp()));
+    match fs::rename(path, &backup) {
+        Ok(()) => debug_log(&format!("Moved unreadable {} to {:?}", path.display(), backup)),
+        Err(e) => debug_log(&format!("Failed to move unreadable {}: {}", path.display(), e)),
+    }
+}
+
+fn read_outbox() -> Vec<OutboxEntry> {
+    let path = get_outbox_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("read_outbox: parse error: {}", e));
+            // Keep the pending uploads for recovery instead of overwriting them
+            set_aside_corrupt_file(&path);
+            Vec::new()
+        }),
+        Err(e) => {
+            debug_log(&format!("read_outbox: read error: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+fn write_outbox(entries: &[OutboxEntry]) -> Result<(), String> {
+    let path = get_outbox_path();
+    let content = serde_json::to_string(entries)
+        .map_err(|e| format!("Failed to serialize outbox: {}", e))?;
+
+    // Write to a temp file first so a crash mid-write can't truncate the outbox
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write outbox: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace outbox: {}", e))
+}
+
+/// Get all pending outbox entries (oldest first)
+pub fn load_outbox() -> Vec<OutboxEntry> {
+    let _guard = OUTBOX_LOCK.lock();
+    read_outbox()
+}
+
+/// Park an upload in the outbox so the background uploader can retry it
+pub fn enqueue_outbox(payload: OutboxPayload, error: &str) -> Result<(), String> {
+    debug_log(&format!(
+        "enqueue_outbox: session={}, error={}",
+        payload.scan_session_id(),
+        error
+    ));
+    let _guard = OUTBOX_LOCK.lock();
+    let now = chrono::Utc::now().timestamp();
+
+    let mut entries = read_outbox();
+    entries.push(OutboxEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        payload,
+        attempts: 1,
+        last_error: Some(error.to_string()),
+        created_at: now,
+        next_attempt_at: now + outbox_backoff_seconds(1),
+    });
+    write_outbox(&entries)
+}
+
+/// Remove an entry after the API accepted it
+pub fn remove_outbox_entry(id: &str) -> Result<(), String> {
+    let _guard = OUTBOX_LOCK.lock();
+    let mut entries = read_outbox();
+    entries.retain(|e| e.id != id);
+    write_outbox(&entries)
+}
+
+/// Record another failed attempt and push the next retry out
+pub fn record_outbox_failure(id: &str, error: &str) -> Result<(), String> {
+    let _guard = OUTBOX_LOCK.lock();
+    let now = chrono::Utc::now().timestamp();
+    let mut entries = read_outbox();
+
+    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+        entry.attempts += 1;
+        entry.last_error = Some(error.to_string());
+        entry.next_attempt_at = now + outbox_backoff_seconds(entry.attempts);
+    }
+
+    write_outbox(&entries)
+}
+
+/// Get the path to the file of uploads the API would not accept
+fn get_rejected_uploads_path() -> PathBuf {
+    get_outbox_path().with_file_name("outbox_rejected.json")
+}
+
+/// Keep an upload that is given up on in `outbox_rejected.json`, so it no longer holds back
+/// its session's finalize but is still there to recover
+pub fn store_rejected_upload(payload: OutboxPayload, attempts: u32, error: &str) -> Result<(), String> {
+    let _guard = OUTBOX_LOCK.lock();
+    let now = chrono::Utc::now().timestamp();
+    append_rejected_upload(OutboxEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        payload,
+        attempts,
+        last_error: Some(error.to_string()),
+        created_at: now,
+        next_attempt_at: now,
+    })
+}
+
+/// Move an outbox entry that is given up on to `outbox_rejected.json`
+pub fn reject_outbox_entry(id: &str, error: &str) -> Result<(), String> {
+    let _guard = OUTBOX_LOCK.lock();
+    let mut entries = read_outbox();
+    let Some(index) = entries.iter().position(|e| e.id == id) else {
+        return Ok(());
+    };
+    let mut entry = entries.remove(index);
+    entry.attempts += 1;
+    entry.last_error = Some(error.to_string());
+
+    append_rejected_upload(entry)?;
+    write_outbox(&entries)
+}
+
+/// Caller holds `OUTBOX_LOCK`
+fn append_rejected_upload(entry: OutboxEntry) -> Result<(), String> {
+    let path = get_rejected_uploads_path();
+    let mut rejected: Vec<OutboxEntry> = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("append_rejected_upload: parse error: {}", e));
+            set_aside_corrupt_file(&path);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+    rejected.push(entry);
+
+    let content = serde_json::to_string(&rejected)
+        .map_err(|e| format!("Failed to serialize rejected uploads: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write rejected uploads: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace rejected uploads: {}", e))
+}
+
+/// Check if results of a scan session are still waiting to be uploaded
+pub fn outbox_has_results_for_session(scan_session_id: &str) -> bool {
+    load_outbox().iter().any(|e| {
+        matches!(e.payload, OutboxPayload::ScanResult { .. }) && e.payload.scan_session_id() == scan_session_id
+    })
+}
+
+/// Attempts before an upload is given up and set aside (about two days of hourly retries)
+pub const MAX_OUTBOX_ATTEMPTS: u32 = 48;
+
+/// Exponential backoff: 30s, 1m, 2m, 4m, ... capped at 1 hour
+fn outbox_backoff_seconds(attempts: u32) -> i64 {
+    const BASE_SECS: i64 = 30;
+    const MAX_SECS: i64 = 60 * 60;
+    let exponent = attempts.saturating_sub(1).min(10);
+    (BASE_SECS << exponent).min(MAX_SECS)
+}
+
+// ============== Scan Checkpoints ==============
+
+/// Progress of one cell of the scan matrix (country × platform × prompt × sample)
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanCellStatus {
+    Pending,
+    Submitted,
+    Collected,
+    Failed,
+    Skipped,
+    /// Not run because the account hit a usage limit; moved to a re-queued job
+    Limited,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScanCell {
+    pub country: String,
+    pub platform: String,
+    pub prompt_id: String,
+    pub sample: usize,
+    pub status: ScanCellStatus,
+    /// Outcome of a collected cell, kept so a resumed scan can report session-wide rates
+    #[serde(default)]
+    pub brand_mentioned: Option<bool>,
+    #[serde(default)]
+    pub citation_present: Option<bool>,
+    #[serde(default)]
+    pub credits_exhausted: Option<bool>,
+    #[serde(default)]
+    pub competitor_mentions: Vec<String>,
+    /// True if the answer was collected after the platform's maximum wait ran out
+    #[serde(default)]
+    pub response_timed_out: Option<bool>,
+}
+
+/// Everything needed to continue a scan session after a crash or quit
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub scan_session_id: String,
+    pub product_id: String,
+    pub samples: usize,
+    pub platforms: Vec<String>,
+    pub countries: Vec<String>,
+    pub prompts: Vec<crate::Prompt>,
+    pub brand: String,
+    #[serde(default)]
+    pub brand_aliases: Vec<String>,
+    #[serde(default)]
+    pub brand_exclusions: Vec<String>,
+    #[serde(default)]
+    pub brand_case_sensitive: bool,
+    pub brand_domain: Option<String>,
+    pub domain_aliases: Option<Vec<String>>,
+    pub competitors: Vec<Competitor>,
+    pub cells: Vec<ScanCell>,
+    /// running, cancelled, failed, complete
+    pub status: String,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+impl ScanCheckpoint {
+    /// How the session matches brand mentions
+    pub fn brand_terms(&self) -> BrandTerms {
+        BrandTerms::new(
+            &self.brand,
+            self.brand_aliases.iter().cloned(),
+            self.brand_exclusions.iter().cloned(),
+            self.brand_case_sensitive,
+        )
+    }
+
+    /// Find the cell for a country/platform/prompt/sample combination
+    pub fn cell_mut(&mut self, country: &str, platform: &str, prompt_id: &str, sample: usize) -> Option<&mut ScanCell> {
+        self.cells.iter_mut().find(|c| {
+            c.country == country && c.platform == platform && c.prompt_id == prompt_id && c.sample == sample
+        })
+    }
+
+    /// Get the status of a cell, if it is part of the matrix
+    pub fn cell_status(&self, country: &str, platform: &str, prompt_id: &str, sample: usize) -> Option<ScanCellStatus> {
+        self.cells.iter()
+            .find(|c| c.country == country && c.platform == platform && c.prompt_id == prompt_id && c.sample == sample)
+            .map(|c| c.status)
+    }
+}
+
+/// Maximum number of finished checkpoints kept on disk
+const MAX_FINISHED_CHECKPOINTS: usize = 20;
+
+/// Days an unfinished (cancelled, failed, paused or abandoned) checkpoint stays resumable
+const UNFINISHED_CHECKPOINT_DAYS: i64 = 14;
+
+fn get_checkpoint_dir() -> PathBuf {
+    get_config_dir().join("scan-sessions")
+}
+
+fn get_checkpoint_path(scan_session_id: &str) -> PathBuf {
+    get_checkpoint_dir().join(format!("{}.json", scan_session_id))
+}
+
+/// Write a scan checkpoint to disk (atomically, via a temp file)
+pub fn save_scan_checkpoint(checkpoint: &ScanCheckpoint) -> Result<(), String> {
+    let dir = get_checkpoint_dir();
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create checkpoint dir: {}", e))?;
+    }
+
+    let path = get_checkpoint_path(&checkpoint.scan_session_id);
+    let content = serde_json::to_string(checkpoint)
+        .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace checkpoint: {}", e))
+}
+
+/// Load the checkpoint of a scan session
+pub fn load_scan_checkpoint(scan_session_id: &str) -> Option<ScanCheckpoint> {
+    let content = fs::read_to_string(get_checkpoint_path(scan_session_id)).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| debug_log(&format!("load_scan_checkpoint: parse error: {}", e)))
+        .ok()
+}
+
+/// Load all checkpoints on disk, most recently updated first
+pub fn list_scan_checkpoints() -> Vec<ScanCheckpoint> {
+    let entries = match fs::read_dir(get_checkpoint_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut checkpoints: Vec<ScanCheckpoint> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().map(|ext| ext == "json").unwrap_or(false))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+
+    checkpoints.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+    checkpoints
+}
+
+/// Delete the checkpoint of a scan session
+pub fn remove_scan_checkpoint(scan_session_id: &str) -> Result<(), String> {
+    debug_log(&format!("remove_scan_checkpoint: {}", scan_session_id));
+    let path = get_checkpoint_path(scan_session_id);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove checkpoint: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Drop the oldest finished checkpoints, keeping the most recent ones for reference, and
+/// unfinished ones not touched for `UNFINISHED_CHECKPOINT_DAYS` unless a queued job still
+/// resumes them
+pub fn prune_scan_checkpoints() {
+    let (finished, unfinished): (Vec<ScanCheckpoint>, Vec<ScanCheckpoint>) = list_scan_checkpoints()
+        .into_iter()
+        .partition(|c| c.status == "complete");
+
+    for checkpoint in finished.iter().skip(MAX_FINISHED_CHECKPOINTS) {
+        let _ = remove_scan_checkpoint(&checkpoint.scan_session_id);
+    }
+
+    let cutoff = chrono::Utc::now().timestamp() - UNFINISHED_CHECKPOINT_DAYS * 24 * 60 * 60;
+    let queued_sessions: Vec<String> = load_scan_queue()
+        .into_iter()
+        .filter_map(|job| job.scan_session_id)
+        .collect();
+    for checkpoint in unfinished {
+        if checkpoint.updated_at < cutoff && !queued_sessions.contains(&checkpoint.scan_session_id) {
+            let _ = remove_scan_checkpoint(&checkpoint.scan_session_id);
+        }
+    }
+}
+
+// ============== Scan Queue ==============
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles on the scan queue file
+    static ref SCAN_QUEUE_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+}
+
+/// Where a queued scan came from
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanJobSource {
+    Manual,
+    Scheduled,
+    /// Prompts deferred by a platform usage limit
+    Requeued,
+}
+
+/// Whether a queued scan is waiting or has been started by the runner
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanJobStatus {
+    #[default]
+    Queued,
+    /// Started; stays in the queue file until it finishes so a crash doesn't lose it
+    Running,
+}
+
+/// A scan waiting in the queue
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanJob {
+    pub id: String,
+    pub product_id: String,
+    pub platforms: Vec<String>,
+    pub samples: usize,
+    #[serde(default)]
+    pub prompt_filter: PromptFilter,
+    /// Higher priority jobs run first; equal priorities run in queue order
+    pub priority: i32,
+    pub source: ScanJobSource,
+    pub enqueued_at: i64,
+    /// Unix seconds before which the job must not start (e.g. until a usage limit resets)
+    #[serde(default)]
+    pub not_before: Option<i64>,
+    /// Times this job's prompts were re-queued in a row by a usage limit with no reset time shown
+    #[serde(default)]
+    pub limit_requeues: u32,
+    #[serde(default)]
+    pub status: ScanJobStatus,
+    /// Session the job's scan runs as, set once it starts; a job cut off by a crash resumes it
+    #[serde(default)]
+    pub scan_session_id: Option<String>,
+}
+
+/// Get the path to the scan queue file
+fn get_scan_queue_path() -> PathBuf {
+    // get_config_path() makes sure the config directory exists
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("scan_queue.json"))
+        .unwrap_or_else(|| get_config_dir().join("scan_queue.json"))
+}
+
+fn read_scan_queue() -> Vec<ScanJob> {
+    let path = get_scan_queue_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("read_scan_queue: parse error: {}", e));
+            // Keep the queued scans for recovery instead of overwriting them
+            set_aside_corrupt_file(&path);
+            Vec::new()
+        }),
+        Err(e) => {
+            debug_log(&format!("read_scan_queue: read error: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+fn write_scan_queue(jobs: &[ScanJob]) -> Result<(), String> {
+    let path = get_scan_queue_path();
+    let content = serde_json::to_string_pretty(jobs)
+        .map_err(|e| format!("Failed to serialize scan queue: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write scan queue: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace scan queue: {}", e))
+}
+
+/// Get all scans waiting to start, in execution order
+pub fn load_scan_queue() -> Vec<ScanJob> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    jobs.retain(|j| j.status == ScanJobStatus::Queued);
+    jobs
+}
+
+/// Add a job behind every queued job of the same or higher priority
+pub fn push_scan_job(job: ScanJob) -> Result<(), String> {
+    debug_log(&format!("push_scan_job: {} product={} priority={}", job.id, job.product_id, job.priority));
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    let position = jobs.iter()
+        .position(|j| j.priority < job.priority)
+        .unwrap_or(jobs.len());
+    jobs.insert(position, job);
+    write_scan_queue(&jobs)
+}
+
+/// Remove a job that has not started from the queue. Returns false if it was not waiting.
+pub fn remove_scan_job(job_id: &str) -> Result<bool, String> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    let before = jobs.len();
+    jobs.retain(|j| j.id != job_id || j.status == ScanJobStatus::Running);
+    if jobs.len() == before {
+        return Ok(false);
+    }
+    write_scan_queue(&jobs).map(|_| true)
+}
+
+/// Put the listed jobs first, in the given order, then re-sort by priority so a reorder never
+/// moves a job ahead of a higher-priority one. Jobs not listed keep their relative order after them.
+pub fn reorder_scan_queue(job_ids: &[String]) -> Result<Vec<ScanJob>, String> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut remaining = read_scan_queue();
+    let mut jobs = Vec::with_capacity(remaining.len());
+
+    for id in job_ids {
+        if let Some(pos) = remaining.iter().position(|j| &j.id == id) {
+            jobs.push(remaining.remove(pos));
+        }
+    }
+    jobs.extend(remaining);
+    // Stable, so the requested order holds among jobs of equal priority
+    jobs.sort_by_key(|j| std::cmp::Reverse(j.priority));
+
+    write_scan_queue(&jobs)?;
+    Ok(jobs)
+}
+
+/// Mark the job that should run next as running and return it.
+/// It stays in the queue until `finish_scan_job` is called.
+pub fn take_next_scan_job() -> Option<ScanJob> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    let now = chrono::Utc::now().timestamp();
+    let job = jobs.iter_mut().find(|j| {
+        j.status == ScanJobStatus::Queued && j.not_before.is_none_or(|t| t <= now)
+    })?;
+    job.status = ScanJobStatus::Running;
+    let job = job.clone();
+    if let Err(e) = write_scan_queue(&jobs) {
+        debug_log(&format!("take_next_scan_job: {}", e));
+    }
+    Some(job)
+}
+
+/// Remove a started job once its scan has completed or failed
+pub fn finish_scan_job(job_id: &str) -> Result<(), String> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    jobs.retain(|j| j.id != job_id);
+    write_scan_queue(&jobs)
+}
+
+/// Record the scan session a started job runs as
+pub fn set_scan_job_session(job_id: &str, scan_session_id: &str) -> Result<(), String> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    if let Some(job) = jobs.iter_mut().find(|j| j.id == job_id) {
+        job.scan_session_id = Some(scan_session_id.to_string());
+    }
+    write_scan_queue(&jobs)
+}
+
+/// Put jobs that were running when the app last stopped back in the queue, keeping their
+/// scan session so the runner resumes it. Returns how many were re-queued.
+pub fn requeue_running_scan_jobs() -> Result<usize, String> {
+    let _guard = SCAN_QUEUE_LOCK.lock();
+    let mut jobs = read_scan_queue();
+    let mut requeued = 0;
+    for job in jobs.iter_mut().filter(|j| j.status == ScanJobStatus::Running) {
+        job.status = ScanJobStatus::Queued;
+        requeued += 1;
+    }
+    if requeued > 0 {
+        write_scan_queue(&jobs)?;
+    }
+    Ok(requeued)
+}
+
+// ============== Scan History ==============
+
+/// Get the path to the local scan history database
+pub fn get_history_db_path() -> PathBuf {
+    // get_config_path() makes sure the config directory exists
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("history.sqlite"))
+        .unwrap_or_else(|| get_config_dir().join("history.sqlite"))
+}
+
+// ============== DOM Snapshots ==============
+
+/// Directory holding the snapshots of one scan session
+pub fn get_snapshot_dir(scan_session_id: &str) -> PathBuf {
+    get_config_dir().join("snapshots").join(scan_session_id)
+}
+
+/// File name of a cell's snapshot; also used as the object name when uploading
+pub fn snapshot_file_name(platform: &str, country: &str, prompt_id: &str, sample: usize) -> String {
+    format!("{}-{}-{}-{}.html.gz", platform, country, prompt_id, sample)
+}
+
+/// Write a gzip-compressed snapshot for a cell and return its path
+pub fn save_snapshot(
+    scan_session_id: &str,
+    platform: &str,
+    country: &str,
+    prompt_id: &str,
+    sample: usize,
+    gzipped_html: &[u8],
+) -> Result<PathBuf, String> {
+    let dir = get_snapshot_dir(scan_session_id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let path = dir.join(snapshot_file_name(platform, country, prompt_id, sample));
+    fs::write(&path, gzipped_html).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    Ok(path)
+}
+
+// ============== Screenshots ==============
+
+/// Directory holding the answer screenshots of one scan session
+pub fn get_screenshot_dir(scan_session_id: &str) -> PathBuf {
+    get_config_dir().join("screenshots").join(scan_session_id)
+}
+
+/// Write a cell's PNG screenshot and return its path
+pub fn save_screenshot(
+    scan_session_id: &str,
+    platform: &str,
+    country: &str,
+    prompt_id: &str,
+    sample: usize,
+    png: &[u8],
+) -> Result<PathBuf, String> {
+    let dir = get_screenshot_dir(scan_session_id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create screenshot directory: {}", e))?;
+
+    let path = dir.join(format!("{}-{}-{}-{}.png", platform, country, prompt_id, sample));
+    fs::write(&path, png).map_err(|e| format!("Failed to write screenshot: {}", e))?;
+    Ok(path)
+}
+
+// ============== Rate Limits ==============
+
+/// Pacing rules for prompts sent to one platform account
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitPolicy {
+    /// Minimum seconds between two prompts on the same account
+    pub min_gap_secs: u64,
+    /// Maximum prompts per account in any rolling hour (0 = unlimited)
+    pub max_per_hour: u32,
+    /// Up to this many random seconds are added to each gap
+    pub jitter_secs: u64,
+}
+
+/// Built-in policy for a platform; ChatGPT and Perplexity are the quickest to flag bursts
+pub fn default_rate_limit(platform: &str) -> RateLimitPolicy {
+    let (min_gap_secs, max_per_hour, jitter_secs) = match platform {
+        "chatgpt" => (20, 40, 10),
+        "perplexity" => (20, 40, 10),
+        "claude" => (15, 40, 8),
+        "gemini" => (10, 60, 5),
+        "google_aio" | "google_ai_mode" => (8, 90, 5),
+        _ => (15, 40, 8),
+    };
+    RateLimitPolicy { min_gap_secs, max_per_hour, jitter_secs }
+}
+
+/// Policy in effect for a platform: the user's override or the built-in default
+pub fn get_rate_limit(platform: &str) -> RateLimitPolicy {
+    load_state()
+        .rate_limits
+        .get(platform)
+        .copied()
+        .unwrap_or_else(|| default_rate_limit(platform))
+}
+
+/// Override the policy for a platform, or go back to the default with `None`
+pub fn set_rate_limit(platform: &str, policy: Option<RateLimitPolicy>) -> Result<(), String> {
+    let mut state = load_state();
+    match policy {
+        Some(p) => state.rate_limits.insert(platform.to_string(), p),
+        None => state.rate_limits.remove(platform),
+    };
+    save_state(&state)
+}
+
+/// Get the path to the file recording recent prompts per account
+fn get_rate_limit_usage_path() -> PathBuf {
+    // get_config_path() makes sure the config directory exists
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("rate_limit_usage.json"))
+        .unwrap_or_else(|| get_config_dir().join("rate_limit_usage.json"))
+}
+
+/// Unix milliseconds of recent prompts, keyed by "{country_code}:{platform}"
+pub fn load_rate_limit_usage() -> HashMap<String, Vec<i64>> {
+    let path = get_rate_limit_usage_path();
+    if !path.exists() {
+        return HashMap::new();
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("load_rate_limit_usage: parse error: {}", e));
+            HashMap::new()
+        }),
+        Err(e) => {
+            debug_log(&format!("load_rate_limit_usage: read error: {}", e));
+            HashMap::new()
+        }
+    }
+}
+
+pub fn save_rate_limit_usage(usage: &HashMap<String, Vec<i64>>) -> Result<(), String> {
+    let path = get_rate_limit_usage_path();
+    let content = serde_json::to_string(usage)
+        .map_err(|e| format!("Failed to serialize rate limit usage: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write rate limit usage: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace rate limit usage: {}", e))
+}
+
+// ============== Webhooks ==============
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles on the delivery log (event senders and the retry worker)
+    static ref WEBHOOK_DELIVERIES_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+}
+
+/// Delivered and failed deliveries kept in the log; pending ones are always kept
+const MAX_WEBHOOK_DELIVERIES: usize = 200;
+
+/// Attempts before a delivery is given up and marked failed
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 8;
+
+/// An HTTP endpoint that receives scan lifecycle events as signed JSON
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    /// HMAC-SHA256 key for the `X-Columbus-Signature` header
+    pub secret: String,
+    /// Events to send, e.g. "scan:complete"; empty sends all
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl WebhookEndpoint {
+    pub fn wants(&self, event: &str) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// One event sent (or being sent) to one endpoint
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub endpoint_id: String,
+    pub url: String,
+    pub event: String,
+    /// JSON body, sent unchanged on every attempt
+    pub body: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, if the endpoint answered
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Earliest time (unix seconds) a pending delivery is attempted again
+    pub next_attempt_at: i64,
+}
+
+pub fn get_webhooks() -> Vec<WebhookEndpoint> {
+    load_state().webhooks
+}
+
+/// Add an endpoint, or replace the one with the same ID
+pub fn save_webhook(endpoint: WebhookEndpoint) -> Result<(), String> {
+    let mut state = load_state();
+    match state.webhooks.iter_mut().find(|w| w.id == endpoint.id) {
+        Some(existing) => *existing = endpoint,
+        None => state.webhooks.push(endpoint),
+    }
+    save_state(&state)
+}
+
+/// Remove an endpoint; its pending deliveries are marked failed
+pub fn remove_webhook(id: &str) -> Result<(), String> {
+    let mut state = load_state();
+    state.webhooks.retain(|w| w.id != id);
+    save_state(&state)?;
+
+    let _guard = WEBHOOK_DELIVERIES_LOCK.lock();
+    let mut deliveries = read_webhook_deliveries();
+    for delivery in deliveries.iter_mut().filter(|d| d.endpoint_id == id && d.status == WebhookDeliveryStatus::Pending) {
+        delivery.status = WebhookDeliveryStatus::Failed;
+        delivery.last_error = Some("Endpoint was removed".to_string());
+        delivery.updated_at = chrono::Utc::now().timestamp();
+    }
+    write_webhook_deliveries(&deliveries)
+}
+
+fn get_webhook_deliveries_path() -> PathBuf {
+    // get_config_path() makes sure the config directory exists
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("webhook_deliveries.json"))
+        .unwrap_or_else(|| get_config_dir().join("webhook_deliveries.json"))
+}
+
+fn read_webhook_deliveries() -> Vec<WebhookDelivery> {
+    let path = get_webhook_deliveries_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            debug_log(&format!("read_webhook_deliveries: parse error: {}", e));
+            Vec::new()
+        }),
+        Err(e) => {
+            debug_log(&format!("read_webhook_deliveries: read error: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+fn write_webhook_deliveries(deliveries: &[WebhookDelivery]) -> Result<(), String> {
+    let path = get_webhook_deliveries_path();
+    let content = serde_json::to_string(deliveries)
+        .map_err(|e| format!("Failed to serialize webhook deliveries: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write webhook deliveries: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace webhook deliveries: {}", e))
+}
+
+/// The delivery log, oldest first
+pub fn load_webhook_deliveries() -> Vec<WebhookDelivery> {
+    let _guard = WEBHOOK_DELIVERIES_LOCK.lock();
+    read_webhook_deliveries()
+}
+
+/// Add a delivery to the log, dropping the oldest finished ones beyond `MAX_WEBHOOK_DELIVERIES`
+pub fn add_webhook_delivery(delivery: WebhookDelivery) -> Result<(), String> {
+    let _guard = WEBHOOK_DELIVERIES_LOCK.lock();
+    let mut deliveries = read_webhook_deliveries();
+    deliveries.push(delivery);
+
+    let finished = deliveries.iter().filter(|d| d.status != WebhookDeliveryStatus::Pending).count();
+    let mut excess = finished.saturating_sub(MAX_WEBHOOK_DELIVERIES);
+    deliveries.retain(|d| {
+        if excess > 0 && d.status != WebhookDeliveryStatus::Pending {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+
+    write_webhook_deliveries(&deliveries)
+}
+
+/// Record the outcome of an attempt: delivered, or failed with a retry scheduled
+/// until `MAX_WEBHOOK_ATTEMPTS` is reached
+pub fn record_webhook_attempt(id: &str, status_code: Option<u16>, error: Option<String>) -> Result<(), String> {
+    let _guard = WEBHOOK_DELIVERIES_LOCK.lock();
+    let now = chrono::Utc::now().timestamp();
+    let mut deliveries = read_webhook_deliveries();
+
+    if let Some(delivery) = deliveries.iter_mut().find(|d| d.id == id) {
+        delivery.attempts += 1;
+        delivery.last_status_code = status_code;
+        delivery.updated_at = now;
+        delivery.status = match &error {
+            None => WebhookDeliveryStatus::Delivered,
+            Some(_) if delivery.attempts >= MAX_WEBHOOK_ATTEMPTS => WebhookDeliveryStatus::Failed,
+            Some(_) => WebhookDeliveryStatus::Pending,
+        };
+        delivery.last_error = error;
+        // Same backoff as the upload outbox
+        delivery.next_attempt_at = now + outbox_backoff_seconds(delivery.attempts);
+    }
+
+    write_webhook_deliveries(&deliveries)
+}
+
+// ============== Notifications ==============
+
+/// Which events show a desktop notification
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    /// A scan finished, with its mention rate
+    #[serde(default = "default_true")]
+    pub scan_complete: bool,
+    /// A scan failed or a queued scan could not start
+    #[serde(default = "default_true")]
+    pub scan_failed: bool,
+    /// A captcha could not be solved automatically
+    #[serde(default = "default_true")]
+    pub captcha: bool,
+    /// A platform was found logged out for a country during a scan
+    #[serde(default = "default_true")]
+    pub logged_out: bool,
+    /// A platform account ran out of credits or hit its usage limit
+    #[serde(default = "default_true")]
+    pub credits_exhausted: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            scan_complete: true,
+            scan_failed: true,
+            captcha: true,
+            logged_out: true,
+            credits_exhausted: true,
+        }
+    }
+}
+
+pub fn get_notification_settings() -> NotificationSettings {
+    load_state().notifications
+}
+
+pub fn set_notification_settings(settings: NotificationSettings) -> Result<(), String> {
+    let mut state = load_state();
+    state.notifications = settings;
+    save_state(&state)
+}
@@ -0,0 +1,181 @@
+//! Webhook notifications for scan lifecycle events
+//!
+//! Besides going to the frontend, scan events are POSTed as JSON to the endpoints in
+//! `PersistedState.webhooks` that subscribe to them:
+//!
+//! - `scan:progress`: milestones only (`started`, `25_percent`, `50_percent`, `75_percent`
+//!   of cells collected, and `platform_finished`)
+//! - `scan:complete`: the `ScanComplete` summary
+//! - `scan:error`: the error message
+//! - `scan:captcha`: a captcha showed up in a webview, and whether it was solved
+//!
+//! The body is `{"id", "event", "timestamp", "scanSessionId", "data"}`. Requests carry
+//! `X-Columbus-Event`, `X-Columbus-Delivery`, `X-Columbus-Timestamp` and
+//! `X-Columbus-Signature: sha256=<hex>`, an HMAC-SHA256 with the endpoint's secret over
+//! `"{timestamp}.{body}"`. Every delivery is logged in `webhook_deliveries.json`; failed
+//! ones are retried with backoff until `MAX_WEBHOOK_ATTEMPTS`.
+
+use crate::storage::{self, WebhookDelivery, WebhookDeliveryStatus, WebhookEndpoint};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tauri::async_runtime;
+use tokio::time::{interval, Duration};
+
+lazy_static::lazy_static! {
+    /// Only one pass may send deliveries at a time (new events vs. timer tick vs. manual retry)
+    static ref FLUSH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// LAN endpoints answer quickly; don't let a dead one hold up the others for long
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Start the background task that retries failed deliveries
+pub fn start_webhook_worker() {
+    async_runtime::spawn(async move {
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            flush_deliveries(false).await;
+        }
+    });
+}
+
+/// Queue an event for every endpoint subscribed to it and start sending right away
+pub fn notify(event: &str, scan_session_id: Option<&str>, data: impl Serialize) {
+    let endpoints: Vec<WebhookEndpoint> = storage::get_webhooks().into_iter().filter(|w| w.wants(event)).collect();
+    if endpoints.is_empty() {
+        return;
+    }
+
+    let data = match serde_json::to_value(data) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("[Webhooks] Failed to serialize {} payload: {}", event, e);
+            return;
+        }
+    };
+    for endpoint in &endpoints {
+        queue_delivery(endpoint, event, scan_session_id, &data);
+    }
+
+    async_runtime::spawn(flush_deliveries(false));
+}
+
+/// Queue an event for one endpoint regardless of its subscriptions and start sending right away
+pub fn notify_endpoint(endpoint: &WebhookEndpoint, event: &str, scan_session_id: Option<&str>, data: serde_json::Value) {
+    queue_delivery(endpoint, event, scan_session_id, &data);
+    async_runtime::spawn(flush_deliveries(false));
+}
+
+fn queue_delivery(endpoint: &WebhookEndpoint, event: &str, scan_session_id: Option<&str>, data: &serde_json::Value) {
+    let now = chrono::Utc::now().timestamp();
+    let id = uuid::Uuid::new_v4().to_string();
+    let body = serde_json::json!({
+        "id": id,
+        "event": event,
+        "timestamp": now,
+        "scanSessionId": scan_session_id,
+        "data": data,
+    })
+    .to_string();
+
+    let delivery = WebhookDelivery {
+        id,
+        endpoint_id: endpoint.id.clone(),
+        url: endpoint.url.clone(),
+        event: event.to_string(),
+        body,
+        status: WebhookDeliveryStatus::Pending,
+        attempts: 0,
+        last_status_code: None,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+        next_attempt_at: now,
+    };
+    if let Err(e) = storage::add_webhook_delivery(delivery) {
+        eprintln!("[Webhooks] Failed to log {} delivery: {}", event, e);
+    }
+}
+
+/// Send every pending delivery that is due, oldest first.
+/// With `force` set, deliveries are retried even if their backoff has not elapsed.
+pub async fn flush_deliveries(force: bool) {
+    let _flush_guard = FLUSH_LOCK.lock().await;
+
+    let now = chrono::Utc::now().timestamp();
+    let due: Vec<WebhookDelivery> = storage::load_webhook_deliveries()
+        .into_iter()
+        .filter(|d| d.status == WebhookDeliveryStatus::Pending && (force || d.next_attempt_at <= now))
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("[Webhooks] Failed to create HTTP client: {}", e);
+            return;
+        }
+    };
+    let endpoints = storage::get_webhooks();
+
+    for delivery in due {
+        // Deliveries of a disabled endpoint wait until it is enabled again
+        let Some(endpoint) = endpoints.iter().find(|e| e.id == delivery.endpoint_id && e.enabled) else {
+            continue;
+        };
+
+        let (status_code, error) = match send(&client, endpoint, &delivery).await {
+            Ok(code) => (Some(code), None),
+            Err((code, e)) => {
+                eprintln!("[Webhooks] {} to {} failed (attempt {}): {}",
+                    delivery.event, endpoint.url, delivery.attempts + 1, e);
+                (code, Some(e))
+            }
+        };
+        if let Err(e) = storage::record_webhook_attempt(&delivery.id, status_code, error) {
+            eprintln!("[Webhooks] Failed to record delivery attempt: {}", e);
+        }
+    }
+}
+
+/// POST a delivery's body; returns the HTTP status, with an error unless it was 2xx
+async fn send(
+    client: &reqwest::Client,
+    endpoint: &WebhookEndpoint,
+    delivery: &WebhookDelivery,
+) -> Result<u16, (Option<u16>, String)> {
+    let timestamp = chrono::Utc::now().timestamp().to_string();
+    let signature = sign(&endpoint.secret, &timestamp, &delivery.body);
+
+    let response = client
+        .post(&endpoint.url)
+        .header("Content-Type", "application/json")
+        .header("X-Columbus-Event", &delivery.event)
+        .header("X-Columbus-Delivery", &delivery.id)
+        .header("X-Columbus-Timestamp", &timestamp)
+        .header("X-Columbus-Signature", format!("sha256={}", signature))
+        .body(delivery.body.clone())
+        .send()
+        .await
+        .map_err(|e| (None, format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("Endpoint answered {}", status)))
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"`
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -1,18 +1,17 @@
-use crate::Citation;
+use crate::analysis::{self, BrandTerms, Competitor};
+use crate::{Citation, CompetitorDetailResult, TextSpan};
 use crate::storage;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use enigo::{Enigo, Keyboard, Key, Settings, Mouse, Button, Coordinate};
+use flate2::{write::GzEncoder, Compression};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
 
-#[derive(Clone, Serialize, Deserialize, Debug, Default)]
-pub struct CompetitorDetail {
-    pub name: String,
-    pub position: Option<i32>,
-    pub sentiment: String,
-}
-
 // Multiple realistic User-Agents to rotate through on captcha detection
 const USER_AGENTS: &[&str] = &[
     // Chrome 131 (latest stable as of Nov 2024)
@@ -28,21 +27,99 @@ const USER_AGENTS: &[&str] = &[
 // Maximum retries for captcha detection
 const MAX_CAPTCHA_RETRIES: usize = 3;
 
+// How often the completion detector polls a webview while an answer is streaming
+const COMPLETION_POLL_MS: u64 = 2000;
+
+// Consecutive polls with unchanged text (and no streaming indicator) before an answer counts as final
+const COMPLETION_STABLE_POLLS: u32 = 2;
+
+// Base64 characters read per round trip when pulling a DOM snapshot or answer render out through the URL hash
+const SNAPSHOT_CHUNK_CHARS: usize = 16000;
+
+/// Outcome of waiting for a platform to finish its answer
+#[derive(Clone, Debug)]
+pub struct CompletionOutcome {
+    /// False if the platform's maximum wait ran out before the answer settled
+    pub completed: bool,
+    /// Time from prompt submission until the answer was considered final (or the timeout hit)
+    pub waited_ms: u64,
+    /// Hash of the latest answer's text when waiting stopped; a follow-up passes it to
+    /// `wait_for_completion` to tell its own answer apart from this one
+    pub answer_hash: String,
+}
+
+/// Whether `render_answer_image` can produce images on this platform. Only WebView2
+/// (Windows) exports a canvas drawn from an SVG foreignObject; WebKit (macOS, Linux) taints it.
+pub const ANSWER_RENDER_SUPPORTED: bool = cfg!(target_os = "windows");
+
+/// Why `render_answer_image` produced no image
+#[derive(Debug, thiserror::Error)]
+pub enum AnswerRenderError {
+    /// The webview engine refuses to export a canvas drawn from a foreignObject (WebKit);
+    /// every later render in the same engine fails the same way
+    #[error("this webview can't export DOM renders (tainted canvas)")]
+    Unsupported,
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<String> for AnswerRenderError {
+    fn from(message: String) -> Self {
+        AnswerRenderError::Failed(message)
+    }
+}
+
+impl From<&str> for AnswerRenderError {
+    fn from(message: &str) -> Self {
+        AnswerRenderError::Failed(message.to_string())
+    }
+}
+
+/// One poll of the completion detector
+#[derive(Clone, Debug, PartialEq)]
+struct CompletionSignal {
+    streaming: bool,
+    text_len: usize,
+    text_hash: String,
+}
+
+/// Whether a platform keeps a chat open for follow-up messages. AI Overviews are one-off
+/// searches, so a follow-up would start an unrelated new one.
+pub fn supports_follow_ups(platform: &str) -> bool {
+    platform != "google_aio"
+}
+
+/// Maximum time to wait for an answer on a platform, counted from submission
+pub fn completion_timeout_secs(platform: &str) -> u64 {
+    match platform {
+        "chatgpt" => 120,
+        "claude" => 120,
+        "gemini" => 150,
+        // Perplexity Pro searches and long research answers take the longest
+        "perplexity" => 180,
+        "google_aio" => 60,
+        "google_ai_mode" => 120,
+        _ => 120,
+    }
+}
+
 #[derive(Default)]
 pub struct WebviewManager {
-    active_webviews: HashSet<String>,
-    user_agent_index: usize,
+    active_webviews: Mutex<HashSet<String>>,
+    user_agent_index: AtomicUsize,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct CollectResponse {
     pub response_text: String,
     pub brand_mentioned: bool,
+    pub brand_spans: Vec<TextSpan>,
     pub citation_present: bool,
     pub position: Option<i32>,
     pub sentiment: String,
+    pub sentiment_score: Option<f64>,
     pub competitor_mentions: Vec<String>,
-    pub competitor_details: Vec<CompetitorDetail>,
+    pub competitor_details: Vec<CompetitorDetailResult>,
     pub citations: Vec<Citation>,
     pub credits_exhausted: bool,
     pub chat_url: Option<String>,
@@ -55,18 +132,18 @@ impl WebviewManager {
 
     /// Get the current User-Agent
     fn get_user_agent(&self) -> &'static str {
-        USER_AGENTS[self.user_agent_index % USER_AGENTS.len()]
+        USER_AGENTS[self.user_agent_index.load(Ordering::Relaxed) % USER_AGENTS.len()]
     }
 
     /// Rotate to next User-Agent
-    fn rotate_user_agent(&mut self) {
-        self.user_agent_index = (self.user_agent_index + 1) % USER_AGENTS.len();
-        eprintln!("Rotated to User-Agent index {}: {}", self.user_agent_index, &self.get_user_agent()[..50]);
+    fn rotate_user_agent(&self) {
+        let index = (self.user_agent_index.fetch_add(1, Ordering::Relaxed) + 1) % USER_AGENTS.len();
+        eprintln!("Rotated to User-Agent index {}: {}", index, &self.get_user_agent()[..50]);
     }
 
     /// Create a webview with the current User-Agent
     fn create_webview_internal(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -77,7 +154,7 @@ impl WebviewManager {
 
     /// Create a webview with optional proxy and data directory for geo-targeting
     fn create_webview_with_options(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -125,14 +202,15 @@ impl WebviewManager {
             .build()
             .map_err(|e| format!("Failed to create webview: {}", e))?;
 
-        self.active_webviews.insert(label.to_string());
-        eprintln!("[Columbus] Created webview '{}', active webviews: {:?}", label, self.active_webviews);
+        let mut active = self.active_webviews.lock();
+        active.insert(label.to_string());
+        eprintln!("[Columbus] Created webview '{}', active webviews: {:?}", label, *active);
         Ok(())
     }
 
     /// Create a webview for a specific country (with proxy and isolated cookies)
     pub async fn create_webview_for_country(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -160,7 +238,7 @@ impl WebviewManager {
 
     /// Create a webview for local (user's actual location, no proxy, but still isolated cookies per platform)
     pub fn create_webview_local(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -218,6 +296,41 @@ impl WebviewManager {
         }
     }
 
+    /// Tell webhook listeners about a captcha, with the running scan's session if there is one,
+    /// and the user once it could not be solved automatically
+    fn report_captcha(&self, app: &AppHandle, label: &str, attempt: usize, solved: bool) {
+        let scan_session_id = app
+            .try_state::<std::sync::Arc<crate::AppState>>()
+            .and_then(|state| {
+                let scan = state.scan.lock();
+                scan.scan_session_id.clone().filter(|_| scan.is_running)
+            });
+        let url = app.get_webview_window(label).and_then(|w| w.url().ok());
+        let host = url.as_ref().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_default();
+
+        crate::webhooks::notify(
+            "scan:captcha",
+            scan_session_id.as_deref(),
+            serde_json::json!({
+                "webviewLabel": label,
+                "url": url.map(|u| u.to_string()),
+                "attempt": attempt,
+                "solved": solved,
+            }),
+        );
+
+        // After the last retry nothing more is tried automatically
+        if !solved && attempt == MAX_CAPTCHA_RETRIES {
+            crate::notifications::notify(
+                app,
+                crate::notifications::NotificationKind::Captcha,
+                &host,
+                "A captcha needs you",
+                &format!("A captcha on {} couldn't be solved automatically. Open the platform and solve it to continue scanning.", host),
+            );
+        }
+    }
+
     /// Check if the current page is a captcha/bot detection page
     pub async fn check_for_captcha(&self, app: &AppHandle, label: &str) -> bool {
         let window = match app.get_webview_window(label) {
@@ -382,7 +495,7 @@ impl WebviewManager {
     /// Create webview with captcha detection, auto-solve attempt, and retry logic
     /// Webviews are invisible by default, shown only when captcha needs user interaction
     pub async fn create_webview(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -413,6 +526,7 @@ impl WebviewManager {
 
                 // Try to solve the captcha automatically
                 let solved = self.try_solve_captcha(app, label).await;
+                self.report_captcha(app, label, attempt + 1, solved);
 
                 if solved {
                     // Captcha was solved, hide the window again if it wasn't meant to be visible
@@ -446,7 +560,7 @@ impl WebviewManager {
     /// Create webview with captcha handling for a specific country (geo-targeted)
     /// Uses proxy and isolated cookie storage for the country/platform combination
     pub async fn create_webview_geo(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -488,6 +602,7 @@ impl WebviewManager {
                 self.show_webview(app, label);
 
                 let solved = self.try_solve_captcha(app, label).await;
+                self.report_captcha(app, label, attempt + 1, solved);
 
                 if solved {
                     self.hide_webview(app, label);
@@ -514,7 +629,7 @@ impl WebviewManager {
 
     /// Create webview with captcha handling for local (no proxy, but isolated cookies per platform)
     pub async fn create_webview_local_async(
-        &mut self,
+        &self,
         app: &AppHandle,
         label: &str,
         url: &str,
@@ -552,6 +667,7 @@ impl WebviewManager {
                 self.show_webview(app, label);
 
                 let solved = self.try_solve_captcha(app, label).await;
+                self.report_captcha(app, label, attempt + 1, solved);
 
                 if solved {
                     self.hide_webview(app, label);
@@ -576,7 +692,7 @@ impl WebviewManager {
         Err("Failed to create local webview without captcha".to_string())
     }
 
-    pub fn close_webview(&mut self, app: &AppHandle, label: &str) {
+    pub fn close_webview(&self, app: &AppHandle, label: &str) {
         eprintln!("[Columbus] close_webview called for label: {}", label);
         if let Some(window) = app.get_webview_window(label) {
             eprintln!("[Columbus] Found webview window '{}', closing...", label);
@@ -592,16 +708,17 @@ impl WebviewManager {
         } else {
             eprintln!("[Columbus] Webview '{}' not found (may already be closed)", label);
         }
-        let was_tracked = self.active_webviews.remove(label);
+        let mut active = self.active_webviews.lock();
+        let was_tracked = active.remove(label);
         eprintln!("[Columbus] Removed '{}' from active_webviews tracking: {}", label, was_tracked);
-        eprintln!("[Columbus] Remaining active webviews: {:?}", self.active_webviews);
+        eprintln!("[Columbus] Remaining active webviews: {:?}", *active);
     }
 
-    pub fn close_all(&mut self, app: &AppHandle) {
-        let count = self.active_webviews.len();
-        eprintln!("[Columbus] close_all called - {} webviews to close: {:?}", count, self.active_webviews);
+    pub fn close_all(&self, app: &AppHandle) {
+        let labels: Vec<String> = self.active_webviews.lock().drain().collect();
+        eprintln!("[Columbus] close_all called - {} webviews to close: {:?}", labels.len(), labels);
 
-        for label in self.active_webviews.drain() {
+        for label in labels {
             eprintln!("[Columbus] close_all: closing '{}'", label);
             if let Some(window) = app.get_webview_window(&label) {
                 // Navigate to about:blank first to help release WebView2 resources
@@ -616,7 +733,7 @@ impl WebviewManager {
                 eprintln!("[Columbus] close_all: webview '{}' not found", label);
             }
         }
-        eprintln!("[Columbus] close_all complete - remaining: {:?}", self.active_webviews);
+        eprintln!("[Columbus] close_all complete - remaining: {:?}", *self.active_webviews.lock());
     }
 
     pub async fn check_login(
@@ -663,15 +780,285 @@ impl WebviewManager {
         Ok(())
     }
 
+    /// Poll a webview until the platform has finished its answer.
+    /// An answer is final once no streaming indicator (stop button, streaming class) is present
+    /// and the response text has not changed for a few polls. Gives up after the platform's
+    /// maximum wait, counted from `submitted_at`. `on_tick` receives the seconds left until then
+    /// and returns false to stop waiting (e.g. the scan was cancelled).
+    /// For a follow-up in an existing chat, `previous_answer` is the `answer_hash` of the turn
+    /// before; the latest answer only counts once it is a different one.
+    pub async fn wait_for_completion(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+        submitted_at: std::time::Instant,
+        previous_answer: Option<&str>,
+        mut on_tick: impl FnMut(u64) -> bool,
+    ) -> Result<CompletionOutcome, String> {
+        let max_wait = std::time::Duration::from_secs(completion_timeout_secs(platform));
+        let mut last_signal: Option<CompletionSignal> = None;
+        let mut stable_polls = 0;
+
+        loop {
+            let elapsed = submitted_at.elapsed();
+            if elapsed >= max_wait {
+                eprintln!("[Completion] {} timed out after {}s", label, elapsed.as_secs());
+                return Ok(CompletionOutcome {
+                    completed: false,
+                    waited_ms: elapsed.as_millis() as u64,
+                    answer_hash: last_signal.map(|s| s.text_hash).unwrap_or_default(),
+                });
+            }
+            if !on_tick((max_wait - elapsed).as_secs()) {
+                return Err("Stopped waiting for completion".to_string());
+            }
+
+            let signal = self.poll_completion_signal(app, label, platform).await?;
+
+            let new_answer = previous_answer.is_none_or(|hash| hash != signal.text_hash);
+            if new_answer && !signal.streaming && signal.text_len > 0 && last_signal.as_ref() == Some(&signal) {
+                stable_polls += 1;
+            } else {
+                stable_polls = 0;
+            }
+
+            if stable_polls >= COMPLETION_STABLE_POLLS {
+                let waited_ms = submitted_at.elapsed().as_millis() as u64;
+                eprintln!("[Completion] {} complete after {}ms ({} chars)", label, waited_ms, signal.text_len);
+                return Ok(CompletionOutcome { completed: true, waited_ms, answer_hash: signal.text_hash });
+            }
+
+            last_signal = Some(signal);
+            tokio::time::sleep(tokio::time::Duration::from_millis(COMPLETION_POLL_MS)).await;
+        }
+    }
+
+    /// Capture the HTML of the response area (or of the whole page) as gzip bytes.
+    /// The page compresses and base64-encodes the snapshot itself; it is then read back
+    /// through the URL hash in chunks, since the hash can't carry a whole page at once.
+    pub async fn capture_snapshot(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+        full_page: bool,
+    ) -> Result<Vec<u8>, String> {
+        let window = app
+            .get_webview_window(label)
+            .ok_or("Webview not found")?;
+
+        let script = get_snapshot_script(platform, full_page);
+        window
+            .eval(&script)
+            .map_err(|e| format!("Script error: {}", e))?;
+
+        // Compression runs asynchronously in the page; wait for it to report the size
+        let mut header: Option<(usize, bool)> = None;
+        for _ in 0..20 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+            let url = window.url().map_err(|e| format!("Failed to get URL: {}", e))?;
+            let url_str = url.as_str();
+
+            if let Some(pos) = url_str.find("#COLUMBUS_SNAPSHOT_ERROR:") {
+                let message = urlencoding::decode(&url_str[pos + 25..]).map(|m| m.into_owned()).unwrap_or_default();
+                return Err(format!("Snapshot script failed: {}", message));
+            }
+            if let Some(pos) = url_str.find("#COLUMBUS_SNAPSHOT:") {
+                let mut parts = url_str[pos + 19..].split(':');
+                let length = parts.next().and_then(|l| l.parse().ok()).unwrap_or(0);
+                let gzipped = parts.next() == Some("1");
+                header = Some((length, gzipped));
+                break;
+            }
+        }
+        let (length, gzipped) = header.ok_or("Snapshot script did not finish")?;
+
+        let bytes = self.read_page_base64(&window, "__columbusSnapshot", length).await?;
+        if gzipped {
+            return Ok(bytes);
+        }
+
+        // Older webviews without CompressionStream send plain HTML; compress it here
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).map_err(|e| format!("Failed to compress snapshot: {}", e))?;
+        encoder.finish().map_err(|e| format!("Failed to compress snapshot: {}", e))
+    }
+
+    /// Look for a usage-limit or out-of-credits notice on the page, ignoring the submitted
+    /// prompt itself. Returns the text around the notice, which may include a reset time.
+    pub async fn check_usage_limit(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+        prompt: &str,
+    ) -> Result<Option<String>, String> {
+        let window = app
+            .get_webview_window(label)
+            .ok_or("Webview not found")?;
+
+        let script = get_usage_limit_script(platform, prompt);
+        window
+            .eval(&script)
+            .map_err(|e| format!("Script error: {}", e))?;
+
+        // Wait a moment for script to execute
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+        let url = window.url().map_err(|e| format!("Failed to get URL: {}", e))?;
+        let url_str = url.as_str();
+
+        // Format: #COLUMBUS_LIMIT:0 or #COLUMBUS_LIMIT:1:<uri-encoded notice text>
+        let status = url_str
+            .find("#COLUMBUS_LIMIT:")
+            .map(|pos| &url_str[pos + 16..])
+            .ok_or("Usage limit script did not run")?;
+
+        match status.strip_prefix("1:") {
+            Some(notice) => Ok(Some(urlencoding::decode(notice).map(|n| n.into_owned()).unwrap_or_default())),
+            None => Ok(None),
+        }
+    }
+
+    /// Render the latest answer's DOM, at its full height, to a PNG. Best effort and
+    /// Windows-only (see `ANSWER_RENDER_SUPPORTED`).
+    /// This is not a pixel screenshot (the webview has no native capture): the page draws a
+    /// clone of the answer into a canvas via an SVG foreignObject, so it works for hidden scan
+    /// webviews but only approximates the page. Styles are inlined from computed values
+    /// rather than the stylesheets, and images become same-sized placeholders because
+    /// cross-origin pixels can't be exported from a canvas.
+    ///
+    /// Engines that taint canvases drawn from a foreignObject (WebKit) can't export the
+    /// render at all; that is reported as `AnswerRenderError::Unsupported`.
+    pub async fn render_answer_image(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+    ) -> Result<Vec<u8>, AnswerRenderError> {
+        let window = app
+            .get_webview_window(label)
+            .ok_or("Webview not found")?;
+
+        let script = get_answer_render_script(platform);
+        window
+            .eval(&script)
+            .map_err(|e| format!("Script error: {}", e))?;
+
+        // Rendering runs asynchronously in the page; wait for it to report the size
+        let mut length: Option<usize> = None;
+        for _ in 0..40 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+            let url = window.url().map_err(|e| format!("Failed to get URL: {}", e))?;
+            let url_str = url.as_str();
+
+            if url_str.contains("#COLUMBUS_RENDER_UNSUPPORTED") {
+                return Err(AnswerRenderError::Unsupported);
+            }
+            if let Some(pos) = url_str.find("#COLUMBUS_RENDER_ERROR:") {
+                let message = urlencoding::decode(&url_str[pos + 23..]).map(|m| m.into_owned()).unwrap_or_default();
+                return Err(format!("Answer render script failed: {}", message).into());
+            }
+            if let Some(pos) = url_str.find("#COLUMBUS_RENDER:") {
+                length = url_str[pos + 17..].parse().ok();
+                break;
+            }
+        }
+        let length = length.ok_or("Answer render script did not finish")?;
+
+        Ok(self.read_page_base64(&window, "__columbusAnswerRender", length).await?)
+    }
+
+    /// Read a base64 string of `length` characters from `window.<global>` through the URL hash,
+    /// in chunks since the hash can't carry a whole page at once. Clears the global afterwards.
+    async fn read_page_base64(
+        &self,
+        window: &tauri::WebviewWindow,
+        global: &str,
+        length: usize,
+    ) -> Result<Vec<u8>, String> {
+        let mut encoded = String::with_capacity(length);
+        let mut chunk_index = 0;
+        while encoded.len() < length {
+            let chunk_script = format!(
+                "window.location.hash = 'COLUMBUS_CHUNK:{}:' + window.{}.substr({}, {});",
+                chunk_index,
+                global,
+                encoded.len(),
+                SNAPSHOT_CHUNK_CHARS
+            );
+            window
+                .eval(&chunk_script)
+                .map_err(|e| format!("Script error: {}", e))?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+            let url = window.url().map_err(|e| format!("Failed to get URL: {}", e))?;
+            let marker = format!("#COLUMBUS_CHUNK:{}:", chunk_index);
+            let chunk = url.as_str()
+                .find(&marker)
+                .map(|pos| url.as_str()[pos + marker.len()..].to_string())
+                .ok_or_else(|| format!("Chunk {} of {} not readable", chunk_index, global))?;
+            if chunk.is_empty() {
+                break;
+            }
+            encoded.push_str(&chunk);
+            chunk_index += 1;
+        }
+
+        let _ = window.eval(format!("delete window.{}; window.location.hash = '';", global));
+
+        BASE64.decode(encoded.as_bytes()).map_err(|e| format!("Invalid data in {}: {}", global, e))
+    }
+
+    /// Run the completion status script once and read its result from the URL hash
+    async fn poll_completion_signal(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+    ) -> Result<CompletionSignal, String> {
+        let window = app
+            .get_webview_window(label)
+            .ok_or("Webview not found")?;
+
+        let script = get_completion_status_script(platform);
+        window
+            .eval(&script)
+            .map_err(|e| format!("Script error: {}", e))?;
+
+        // Wait a moment for script to execute
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+        let url = window.url().map_err(|e| format!("Failed to get URL: {}", e))?;
+        let url_str = url.as_str();
+
+        // Format: #COLUMBUS_STATUS:<streaming 0|1>:<text length>:<text hash>
+        let status = match url_str.find("#COLUMBUS_STATUS:") {
+            Some(pos) => &url_str[pos + 17..],
+            None => {
+                // Script didn't run (page still navigating) - treat as still streaming
+                return Ok(CompletionSignal { streaming: true, text_len: 0, text_hash: String::new() });
+            }
+        };
+
+        let mut parts = status.splitn(3, ':');
+        let streaming = parts.next() == Some("1");
+        let text_len = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        let text_hash = parts.next().unwrap_or("").to_string();
+
+        Ok(CompletionSignal { streaming, text_len, text_hash })
+    }
+
     pub async fn collect_response(
         &self,
         app: &AppHandle,
         label: &str,
         platform: &str,
-        brand: &str,
+        brand: &BrandTerms,
         brand_domain: Option<&str>,
         domain_aliases: Option<&[String]>,
-        competitors: &[String],
+        competitors: &[Competitor],
     ) -> Result<CollectResponse, String> {
         let window = app
             .get_webview_window(label)
@@ -774,9 +1161,57 @@ impl WebviewManager {
         }
 
         // Inject script that collects response and sets location.hash with encoded result
-        let script = get_collect_script(platform, brand, brand_domain, domain_aliases, competitors);
+        let script = get_collect_script(platform, brand_domain, domain_aliases);
+        match self.run_collect_script(&window, &script).await {
+            Ok(response) => Ok(analyze_response(response, brand, competitors)),
+            Err(e) => {
+                eprintln!("{}", e);
+                // Fallback: return empty response if parsing failed
+                Ok(CollectResponse::default())
+            }
+        }
+    }
+
+    /// Load an archived DOM snapshot into a hidden webview and run the current collect
+    /// script over it. Scripts in the snapshot are stripped; `base_url` resolves relative links.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn reextract_snapshot(
+        &self,
+        app: &AppHandle,
+        label: &str,
+        platform: &str,
+        html: &str,
+        base_url: Option<&str>,
+        brand: &BrandTerms,
+        brand_domain: Option<&str>,
+        domain_aliases: Option<&[String]>,
+        competitors: &[Competitor],
+    ) -> Result<CollectResponse, String> {
+        self.create_webview_internal(app, label, "about:blank", false)?;
+
+        let result = async {
+            let window = app
+                .get_webview_window(label)
+                .ok_or("Webview not found")?;
+
+            window
+                .eval(get_load_snapshot_script(html, base_url))
+                .map_err(|e| format!("Script error: {}", e))?;
+            // Give the page a moment to lay out so innerText matches what was rendered
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+            let script = get_collect_script(platform, brand_domain, domain_aliases);
+            self.run_collect_script(&window, &script).await.map(|response| analyze_response(response, brand, competitors))
+        }.await;
+
+        self.close_webview(app, label);
+        result
+    }
+
+    /// Evaluate a collect script and read its result from the URL hash
+    async fn run_collect_script(&self, window: &tauri::WebviewWindow, script: &str) -> Result<CollectResponse, String> {
         window
-            .eval(&script)
+            .eval(script)
             .map_err(|e| format!("Script error: {}", e))?;
 
         // Wait for script to execute and set the hash
@@ -789,28 +1224,31 @@ impl WebviewManager {
         eprintln!("Window URL: {}", &url_str[..url_str.len().min(200)]);
 
         // Parse the result from URL hash
-        if let Some(hash_pos) = url_str.find("#COLUMBUS_RESULT:") {
-            let data = &url_str[hash_pos + 17..]; // Skip "#COLUMBUS_RESULT:"
-            // Decode base64 and parse JSON
-            match decode_base64_and_parse(data) {
-                Ok(response) => {
-                    eprintln!("Successfully parsed response: brand_mentioned={}, citation_present={}, text_len={}",
-                             response.brand_mentioned, response.citation_present, response.response_text.len());
-                    return Ok(response);
-                }
-                Err(e) => {
-                    eprintln!("Failed to parse result from hash: {}", e);
-                }
-            }
-        } else {
-            eprintln!("URL doesn't contain result marker");
-        }
-
-        // Fallback: return empty response if parsing failed
-        Ok(CollectResponse::default())
+        let hash_pos = url_str.find("#COLUMBUS_RESULT:").ok_or("URL doesn't contain result marker")?;
+        let data = &url_str[hash_pos + 17..]; // Skip "#COLUMBUS_RESULT:"
+        // Decode base64 and parse JSON
+        let response = decode_base64_and_parse(data)
+            .map_err(|e| format!("Failed to parse result from hash: {}", e))?;
+
+        eprintln!("Successfully parsed response: citation_present={}, text_len={}",
+                 response.citation_present, response.response_text.len());
+        Ok(response)
     }
 }
 
+/// Fill in what is detected in Rust rather than by the collect script
+fn analyze_response(mut response: CollectResponse, brand: &BrandTerms, competitors: &[Competitor]) -> CollectResponse {
+    let found = analysis::analyze(&response.response_text, brand, competitors);
+    response.brand_mentioned = found.brand_mentioned();
+    response.competitor_mentions = found.competitor_mentions();
+    response.brand_spans = found.brand_spans;
+    response.position = found.position;
+    response.sentiment = found.sentiment;
+    response.sentiment_score = found.sentiment_score;
+    response.competitor_details = found.competitor_details;
+    response
+}
+
 fn decode_base64_and_parse(data: &str) -> Result<CollectResponse, String> {
     use std::str;
 
@@ -832,37 +1270,17 @@ fn decode_base64_and_parse(data: &str) -> Result<CollectResponse, String> {
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string(),
-        brand_mentioned: parsed.get("brandMentioned")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false),
+        // Mentions, position and sentiment are filled in by `analyze_response`
+        brand_mentioned: false,
+        brand_spans: Vec::new(),
         citation_present: parsed.get("citationPresent")
             .and_then(|v| v.as_bool())
             .unwrap_or(false),
-        position: parsed.get("position")
-            .and_then(|v| v.as_i64())
-            .map(|v| v as i32),
-        sentiment: parsed.get("sentiment")
-            .and_then(|v| v.as_str())
-            .unwrap_or("neutral")
-            .to_string(),
-        competitor_mentions: parsed.get("competitorMentions")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect())
-            .unwrap_or_default(),
-        competitor_details: parsed.get("competitorDetails")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter()
-                .filter_map(|v| {
-                    Some(CompetitorDetail {
-                        name: v.get("name")?.as_str()?.to_string(),
-                        position: v.get("position").and_then(|p| p.as_i64()).map(|p| p as i32),
-                        sentiment: v.get("sentiment").and_then(|s| s.as_str()).unwrap_or("neutral").to_string(),
-                    })
-                })
-                .collect())
-            .unwrap_or_default(),
+        position: None,
+        sentiment: "neutral".to_string(),
+        sentiment_score: None,
+        competitor_mentions: Vec::new(),
+        competitor_details: Vec::new(),
         citations: parsed.get("citations")
             .and_then(|v| v.as_array())
             .map(|arr| arr.iter()
@@ -912,6 +1330,348 @@ fn base64_decode(input: &str) -> Result<Vec<u8>, String> {
     Ok(result)
 }
 
+/// Phrases in a platform's limit notice that mean the account has run out of messages.
+/// Only matched against the notice elements from `get_limit_notice_selectors`, never the
+/// answer; upsell copy ("Upgrade to Plus") is left out because free-tier pages always show it.
+fn get_credit_indicators(platform: &str) -> &'static [&'static str] {
+    match platform {
+        "chatgpt" => &[
+            "you've reached the limit",
+            "you've hit the limit",
+            "reached your limit",
+            "message limit",
+            "limit reached",
+            "usage cap",
+            "too many requests",
+        ],
+        "claude" => &[
+            "run out of messages",
+            "out of free messages",
+            "usage limit",
+            "message limit reached",
+            "you've used all",
+            "limit reached",
+        ],
+        "gemini" => &[
+            "quota exceeded",
+            "limit reached",
+            "too many requests",
+            "usage limit",
+        ],
+        "perplexity" => &[
+            "reached your limit",
+            "out of searches",
+            "limit reached",
+            "pro search limit",
+            "daily limit",
+        ],
+        _ => &[],
+    }
+}
+
+/// Selectors for the banners, toasts and alerts a platform shows a usage limit in
+fn get_limit_notice_selectors(platform: &str) -> Vec<&'static str> {
+    let mut selectors = vec![
+        r#"[role="alert"]"#,
+        r#"[role="alertdialog"]"#,
+        r#"[role="status"]"#,
+        r#"[aria-live="assertive"]"#,
+        r#"[class*="toast"]"#,
+    ];
+    selectors.extend_from_slice(match platform {
+        "chatgpt" => &[r#"[data-testid*="limit"]"#, r#"[class*="text-token-text-error"]"#][..],
+        "claude" => &[r#"[data-testid*="limit"]"#, r#"[class*="rate-limit"]"#],
+        "gemini" => &["mat-snack-bar-container", ".mat-mdc-snack-bar-label", r#"[class*="quota"]"#],
+        "perplexity" => &[r#"[data-testid*="limit"]"#, r#"[class*="limit-banner"]"#],
+        _ => &[],
+    });
+    selectors
+}
+
+/// JS function `columbusLimitNotices()` returning the lowercased text of the page's limit
+/// notice elements, skipping anything inside (or wrapping) an answer so the model's own
+/// words never count as a limit
+fn get_limit_notice_fn(platform: &str) -> String {
+    let notice_selectors = serde_json::to_string(&get_limit_notice_selectors(platform)).unwrap_or_default();
+    let response_selectors = serde_json::to_string(get_response_selectors(platform)).unwrap_or_default();
+
+    format!(r#"
+            function columbusLimitNotices() {{
+                const noticeSelectors = {};
+                const responseSelectors = {};
+                const inAnswer = el => responseSelectors.some(sel => el.closest(sel) || el.querySelector(sel));
+
+                const notices = [];
+                for (const sel of noticeSelectors) {{
+                    for (const el of document.querySelectorAll(sel)) {{
+                        const text = (el.innerText || '').trim().toLowerCase();
+                        if (text && !inAnswer(el) && !notices.includes(text)) {{
+                            notices.push(text);
+                        }}
+                    }}
+                }}
+                return notices;
+            }}
+    "#, notice_selectors, response_selectors)
+}
+
+/// Selectors for the assistant's answer on each platform (most specific first)
+fn get_response_selectors(platform: &str) -> &'static [&'static str] {
+    match platform {
+        "chatgpt" => &[r#"[data-message-author-role="assistant"]"#, ".agent-turn .markdown", r#"[class*="markdown"]"#, ".prose"],
+        "claude" => &[r#"[data-testid="message-content"]"#, ".font-claude-message", r#"[class*="claude-message"]"#, ".prose"],
+        "gemini" => &[".model-response-text", "message-content", r#"[data-message-author-role="model"]"#, ".response-content"],
+        "perplexity" => &[r#"[data-testid="answer-content"]"#, ".prose", ".markdown", r#"[class*="answer"]"#],
+        "google_aio" => &["div.EyBRub", "div.pOOWX", r#"div[jsname="dvXlsc"]"#],
+        "google_ai_mode" => &[r#"div[data-container-id="main-col"]"#, "div.mZJni"],
+        _ => &[".prose", ".markdown", r#"[class*="response"]"#],
+    }
+}
+
+/// Selectors that only exist while a platform is still generating its answer
+fn get_streaming_selectors(platform: &str) -> &'static [&'static str] {
+    match platform {
+        "chatgpt" => &[r#"[data-testid="stop-button"]"#, r#"button[aria-label*="Stop"]"#, ".result-streaming"],
+        "claude" => &[r#"button[aria-label="Stop response"]"#, r#"[data-is-streaming="true"]"#],
+        "gemini" => &[r#"button[aria-label*="Stop"]"#, r#"mat-icon[fonticon="stop"]"#, ".stop-icon"],
+        "perplexity" => &[r#"[data-testid="stop-generating-response-button"]"#, r#"button[aria-label*="Stop"]"#],
+        // Google doesn't show a stop button; rely on text stability and loading placeholders
+        "google_aio" | "google_ai_mode" => &[r#"[aria-busy="true"]"#, r#"div[jsname="dvXlsc"] [role="progressbar"]"#],
+        _ => &[r#"button[aria-label*="Stop"]"#],
+    }
+}
+
+/// Serialize the latest answer (or the whole page), gzip it in the page when possible and
+/// keep it base64-encoded in `window.__columbusSnapshot` for chunked reading
+fn get_snapshot_script(platform: &str, full_page: bool) -> String {
+    let response_selectors = serde_json::to_string(get_response_selectors(platform)).unwrap_or_default();
+
+    format!(r#"
+        (async function() {{
+            try {{
+                const responseSelectors = {};
+                const fullPage = {};
+
+                let html = null;
+                if (!fullPage) {{
+                    for (const sel of responseSelectors) {{
+                        const els = document.querySelectorAll(sel);
+                        if (els.length > 0) {{
+                            html = els[els.length - 1].outerHTML;
+                            break;
+                        }}
+                    }}
+                }}
+                // Fall back to the whole page if the response area can't be found
+                if (!html) {{
+                    html = '<!DOCTYPE html>' + document.documentElement.outerHTML;
+                }}
+
+                let bytes = new TextEncoder().encode(html);
+                let gzipped = 0;
+                if (typeof CompressionStream !== 'undefined') {{
+                    const stream = new Blob([bytes]).stream().pipeThrough(new CompressionStream('gzip'));
+                    bytes = new Uint8Array(await new Response(stream).arrayBuffer());
+                    gzipped = 1;
+                }}
+
+                let binary = '';
+                for (let i = 0; i < bytes.length; i += 0x8000) {{
+                    binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+                }}
+                window.__columbusSnapshot = btoa(binary);
+                window.location.hash = 'COLUMBUS_SNAPSHOT:' + window.__columbusSnapshot.length + ':' + gzipped;
+            }} catch (e) {{
+                window.location.hash = 'COLUMBUS_SNAPSHOT_ERROR:' + encodeURIComponent(e.message);
+            }}
+        }})();
+    "#, response_selectors, full_page)
+}
+
+/// Search the platform's limit notices (minus the submitted prompt) for its limit phrases
+fn get_usage_limit_script(platform: &str, prompt: &str) -> String {
+    let indicators_json = serde_json::to_string(get_credit_indicators(platform)).unwrap_or_default();
+    let prompt_json = serde_json::to_string(prompt).unwrap_or_default();
+
+    format!(r#"
+        (function() {{
+            {}
+            const indicators = {};
+            const prompt = {}.toLowerCase();
+
+            for (let notice of columbusLimitNotices()) {{
+                // The prompt may itself mention limits; leave it out of the search
+                if (prompt) {{
+                    notice = notice.split(prompt).join(' ');
+                }}
+                if (indicators.some(indicator => notice.includes(indicator))) {{
+                    window.location.hash = 'COLUMBUS_LIMIT:1:' + encodeURIComponent(notice.replace(/\s+/g, ' ').substring(0, 400));
+                    return;
+                }}
+            }}
+            window.location.hash = 'COLUMBUS_LIMIT:0';
+        }})();
+    "#, get_limit_notice_fn(platform), indicators_json, prompt_json)
+}
+
+/// Draw a clone of the latest answer's DOM into a canvas at its full height and keep the PNG
+/// base64-encoded in `window.__columbusAnswerRender` for chunked reading
+fn get_answer_render_script(platform: &str) -> String {
+    let response_selectors = serde_json::to_string(get_response_selectors(platform)).unwrap_or_default();
+
+    format!(r#"
+        (async function() {{
+            try {{
+                const responseSelectors = {};
+                // Browsers refuse canvases much taller than this
+                const maxHeight = 30000;
+                // Computed styles copied onto the clone so it renders outside the page's stylesheets
+                const styleProps = [
+                    'display', 'box-sizing', 'width', 'margin', 'padding', 'border', 'border-radius',
+                    'background-color', 'color', 'font-family', 'font-size', 'font-weight', 'font-style',
+                    'line-height', 'letter-spacing', 'text-align', 'text-decoration', 'white-space',
+                    'list-style', 'vertical-align', 'overflow-wrap'
+                ];
+
+                let target = null;
+                for (const sel of responseSelectors) {{
+                    const els = document.querySelectorAll(sel);
+                    if (els.length > 0) {{
+                        target = els[els.length - 1];
+                        break;
+                    }}
+                }}
+                if (!target) throw new Error('Response area not found');
+
+                const inlineStyles = (source, clone) => {{
+                    const computed = getComputedStyle(source);
+                    clone.setAttribute('style', styleProps.map(p => p + ':' + computed.getPropertyValue(p)).join(';'));
+                    for (let i = 0; i < source.children.length; i++) {{
+                        inlineStyles(source.children[i], clone.children[i]);
+                    }}
+                }};
+
+                const clone = target.cloneNode(true);
+                inlineStyles(target, clone);
+                clone.querySelectorAll('script, iframe, video, canvas').forEach(el => el.remove());
+                const sourceImages = target.querySelectorAll('img, svg');
+                clone.querySelectorAll('img, svg').forEach((img, i) => {{
+                    const rect = sourceImages[i]?.getBoundingClientRect();
+                    const placeholder = document.createElement('span');
+                    placeholder.setAttribute('style', 'display:inline-block;background:#e5e7eb;width:' +
+                        (rect?.width || 0) + 'px;height:' + (rect?.height || 0) + 'px');
+                    img.replaceWith(placeholder);
+                }});
+
+                const width = Math.ceil(target.getBoundingClientRect().width) || 800;
+                const height = Math.min(Math.ceil(target.scrollHeight) || 1, maxHeight);
+                const scale = Math.min(window.devicePixelRatio || 1, maxHeight / height);
+                const background = getComputedStyle(document.body).backgroundColor || '#ffffff';
+
+                const xhtml = new XMLSerializer().serializeToString(clone);
+                const svg = '<svg xmlns="http://www.w3.org/2000/svg" width="' + width + '" height="' + height + '">' +
+                    '<foreignObject width="100%" height="100%">' +
+                    '<div xmlns="http://www.w3.org/1999/xhtml" style="width:' + width + 'px">' + xhtml + '</div>' +
+                    '</foreignObject></svg>';
+
+                const image = new Image();
+                await new Promise((resolve, reject) => {{
+                    image.onload = resolve;
+                    image.onerror = () => reject(new Error('Failed to render answer'));
+                    image.src = 'data:image/svg+xml;charset=utf-8,' + encodeURIComponent(svg);
+                }});
+
+                const canvas = document.createElement('canvas');
+                canvas.width = Math.ceil(width * scale);
+                canvas.height = Math.ceil(height * scale);
+                const ctx = canvas.getContext('2d');
+                ctx.fillStyle = background === 'rgba(0, 0, 0, 0)' ? '#ffffff' : background;
+                ctx.fillRect(0, 0, canvas.width, canvas.height);
+                ctx.scale(scale, scale);
+                ctx.drawImage(image, 0, 0);
+
+                // WebKit taints canvases that drew a foreignObject and refuses to export them
+                let blob;
+                try {{
+                    blob = await new Promise(resolve => canvas.toBlob(resolve, 'image/png'));
+                }} catch (e) {{
+                    if (e.name === 'SecurityError') {{
+                        window.location.hash = 'COLUMBUS_RENDER_UNSUPPORTED';
+                        return;
+                    }}
+                    throw e;
+                }}
+                if (!blob) throw new Error('Failed to encode PNG');
+                const bytes = new Uint8Array(await blob.arrayBuffer());
+
+                let binary = '';
+                for (let i = 0; i < bytes.length; i += 0x8000) {{
+                    binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+                }}
+                window.__columbusAnswerRender = btoa(binary);
+                window.location.hash = 'COLUMBUS_RENDER:' + window.__columbusAnswerRender.length;
+            }} catch (e) {{
+                window.location.hash = 'COLUMBUS_RENDER_ERROR:' + encodeURIComponent(e.message);
+            }}
+        }})();
+    "#, response_selectors)
+}
+
+/// Replace the blank page with an archived snapshot. The snapshot is parsed with DOMParser
+/// and its scripts removed, so nothing from the archived page runs.
+fn get_load_snapshot_script(html: &str, base_url: Option<&str>) -> String {
+    let html_json = serde_json::to_string(html).unwrap_or_default();
+    let base_json = serde_json::to_string(&base_url).unwrap_or_default();
+
+    format!(r#"
+        (function() {{
+            const doc = new DOMParser().parseFromString({}, 'text/html');
+            const baseUrl = {};
+            if (baseUrl) {{
+                const base = doc.createElement('base');
+                base.href = baseUrl;
+                doc.head.prepend(base);
+            }}
+            doc.querySelectorAll('script').forEach(s => s.remove());
+            document.replaceChild(document.adoptNode(doc.documentElement), document.documentElement);
+        }})();
+    "#, html_json, base_json)
+}
+
+fn get_completion_status_script(platform: &str) -> String {
+    let response_selectors = serde_json::to_string(get_response_selectors(platform)).unwrap_or_default();
+    let streaming_selectors = serde_json::to_string(get_streaming_selectors(platform)).unwrap_or_default();
+
+    format!(r#"
+        (function() {{
+            const responseSelectors = {};
+            const streamingSelectors = {};
+
+            const streaming = streamingSelectors.some(sel => {{
+                try {{ return !!document.querySelector(sel); }} catch {{ return false; }}
+            }});
+
+            // Text of the most recent answer
+            let text = '';
+            for (const sel of responseSelectors) {{
+                const els = document.querySelectorAll(sel);
+                if (els.length > 0) {{
+                    text = els[els.length - 1].innerText || '';
+                    if (text.length > 0) break;
+                }}
+            }}
+
+            // djb2 hash so Rust can tell whether the text changed between polls
+            let hash = 5381;
+            for (let i = 0; i < text.length; i++) {{
+                hash = ((hash << 5) + hash + text.charCodeAt(i)) | 0;
+            }}
+
+            window.location.hash = 'COLUMBUS_STATUS:' + (streaming ? '1' : '0') + ':' + text.length + ':' + (hash >>> 0).toString(16);
+        }})();
+    "#, response_selectors, streaming_selectors)
+}
+
 fn get_login_check_script(platform: &str) -> String {
     format!(r#"
         console.log('[Columbus] Checking login for {}');
@@ -1358,21 +2118,18 @@ fn get_submit_script(platform: &str, prompt: &str) -> String {
     }
 }
 
-fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, domain_aliases: Option<&[String]>, competitors: &[String]) -> String {
-    let escaped_brand = brand.replace('\\', "\\\\").replace('"', "\\\"");
+fn get_collect_script(platform: &str, brand_domain: Option<&str>, domain_aliases: Option<&[String]>) -> String {
     let escaped_domain = brand_domain
         .map(|d| d.replace('\\', "\\\\").replace('"', "\\\""))
         .unwrap_or_default();
     let aliases_json = serde_json::to_string(&domain_aliases.unwrap_or(&[])).unwrap_or_default();
-    let competitors_json = serde_json::to_string(competitors).unwrap_or_default();
+    let credit_indicators_json = serde_json::to_string(get_credit_indicators(platform)).unwrap_or_default();
 
     format!(r#"
         (function() {{
             console.log('[Columbus] Collecting response for platform: {}');
-            const brand = "{}";
             const brandDomain = "{}";
             const domainAliases = {};
-            const competitors = {};
 
             // Platform-specific response selectors from working extension
             const platformSelectors = {{
@@ -1420,51 +2177,10 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
             }};
 
             // Platform-specific credit exhaustion indicators
-            const creditIndicators = {{
-                'chatgpt': [
-                    "you've reached the limit",
-                    "you've hit the limit",
-                    "reached your limit",
-                    "message limit",
-                    "upgrade to plus",
-                    "upgrade to chatgpt plus",
-                    "limit reached",
-                    "usage cap",
-                    "too many requests",
-                    "rate limit"
-                ],
-                'claude': [
-                    "run out of messages",
-                    "out of free messages",
-                    "usage limit",
-                    "message limit reached",
-                    "you've used all",
-                    "upgrade to claude pro",
-                    "limit reached",
-                    "wait until",
-                    "rate limit"
-                ],
-                'gemini': [
-                    "quota exceeded",
-                    "limit reached",
-                    "too many requests",
-                    "try again later",
-                    "rate limit",
-                    "usage limit"
-                ],
-                'perplexity': [
-                    "upgrade to pro",
-                    "reached your limit",
-                    "out of searches",
-                    "limit reached",
-                    "pro search limit",
-                    "daily limit"
-                ]
-            }};
+            const creditChecks = {};
 
             const platform = '{}';
             const selectors = platformSelectors[platform] || Object.values(platformSelectors).flat();
-            const creditChecks = creditIndicators[platform] || [];
 
             // Get response text
             let responseText = '';
@@ -1505,10 +2221,10 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
                 responseText = '';
             }}
 
-            // Check for credit exhaustion
-            const pageText = document.body?.innerText?.toLowerCase() || '';
-            const creditsExhausted = creditChecks.some(indicator =>
-                pageText.includes(indicator.toLowerCase())
+            // Check for credit exhaustion in the platform's limit notices (not the answer)
+            {}
+            const creditsExhausted = columbusLimitNotices().some(notice =>
+                creditChecks.some(indicator => notice.includes(indicator))
             );
             console.log('[Columbus] Credits exhausted:', creditsExhausted);
 
@@ -1516,29 +2232,7 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
             const chatUrl = window.location.href.split('#')[0];
             console.log('[Columbus] Chat URL:', chatUrl);
 
-            // Check brand mention (basic text search - AI worker will do proper evaluation)
-            const brandLower = brand.toLowerCase();
-            const responseLower = responseText.toLowerCase();
-            const brandMentioned = responseLower.includes(brandLower);
-            console.log('[Columbus] Brand "' + brand + '" mentioned:', brandMentioned);
-
-            // Check which competitors are mentioned (names only - AI worker will evaluate position/sentiment)
-            const competitorMentions = [];
-            const competitorDetails = [];
-            for (const c of competitors) {{
-                const cLower = c.toLowerCase();
-                if (responseLower.includes(cLower)) {{
-                    competitorMentions.push(c);
-                    // Position and sentiment will be evaluated by AI worker
-                    competitorDetails.push({{
-                        name: c,
-                        position: null,
-                        sentiment: 'neutral'
-                    }});
-                }}
-            }}
-
-            console.log('[Columbus] Competitor mentions:', competitorMentions);
+            // Brand and competitor mentions are matched in Rust on the returned text
 
             // Extract citations (links) using platform-specific selectors
             const citations = [];
@@ -1882,16 +2576,11 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
             }}
 
             // Build result object
-            // Position and sentiment will be evaluated by AI worker - we only collect raw data here
+            // Mentions, position and sentiment are analyzed in Rust - we only collect raw data here
             // citationPresent = true only if the brand's website was cited, not just any citation exists
             const result = {{
                 responseText: responseText.substring(0, 10000), // Limit size
-                brandMentioned,
                 citationPresent: brandCited,
-                position: null,  // AI worker will evaluate
-                sentiment: 'neutral',  // AI worker will evaluate
-                competitorMentions,
-                competitorDetails,  // Position/sentiment will be filled by AI worker
                 citations: citations.slice(0, 10), // Limit citations (all citations, not just brand)
                 creditsExhausted,
                 chatUrl: responseText.length > 0 ? chatUrl : null
@@ -1902,9 +2591,7 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
 
             console.log('[Columbus] Collection complete:', JSON.stringify({{
                 responseLen: responseText.length,
-                brandMentioned,
                 brandCited,
-                competitorCount: competitorMentions.length,
                 citationCount: citations.length,
                 creditsExhausted,
                 chatUrl: result.chatUrl
@@ -1922,7 +2609,7 @@ fn get_collect_script(platform: &str, brand: &str, brand_domain: Option<&str>, d
 
             return result;
         }})();
-    "#, platform, escaped_brand, escaped_domain, aliases_json, competitors_json, platform)
+    "#, platform, escaped_domain, aliases_json, credit_indicators_json, platform, get_limit_notice_fn(platform))
 }
 
 fn get_read_response_script(_platform: &str, _brand: &str, _competitors: &[String]) -> String {
@@ -7,6 +7,7 @@ let currentUser = null;
 let products = [];
 let selectedProductId = null;
 let isScanning = false;
+let queuedScanJobId = null; // Manual scan waiting behind another one
 let isInitializing = true;
 
 // Region/Auth state
@@ -940,17 +941,23 @@ async function handleStartScan() {
 
         // Start the scan
         scanBtn.disabled = true;
-        isScanning = true;
-        resetProgressUI();
-        showView('scanning');
 
-        await invoke('start_scan', {
+        const job = await invoke('start_scan', {
             productId: selectedProductId,
             samplesPerPrompt: parseInt(samplesPerPrompt.value) || 1,
             platforms: authPlatforms
         });
 
-        console.log('Scan started with platforms:', authPlatforms);
+        if (job.status === 'running') {
+            showScanStarted();
+            console.log('Scan started with platforms:', authPlatforms);
+        } else {
+            // Another scan is running; follow this one once the queue starts it
+            queuedScanJobId = job.id;
+            scanBtn.disabled = false;
+            scanInfo.textContent = 'Scan queued - it starts when the running scan finishes';
+            console.log('Scan queued with platforms:', authPlatforms);
+        }
     } catch (error) {
         console.error('Start scan error:', error);
         alert('Failed to start scan: ' + error);
@@ -960,6 +967,13 @@ async function handleStartScan() {
     }
 }
 
+function showScanStarted() {
+    scanBtn.disabled = true;
+    isScanning = true;
+    resetProgressUI();
+    showView('scanning');
+}
+
 function showAuthRequiredModal(missingAuth) {
     const list = document.getElementById('authRequiredList');
     const message = document.getElementById('authRequiredMessage');
@@ -1061,10 +1075,31 @@ async function setupScanEventListeners() {
         handleScanError(event.payload);
     });
 
+    // A scan queued from this window starts once the one ahead of it is done
+    await listen('scan:queue', (event) => {
+        if (queuedScanJobId && event.payload?.current?.id === queuedScanJobId) {
+            queuedScanJobId = null;
+            showScanStarted();
+        }
+    });
+
     await listen('scan:countdown', (event) => {
         updateCountdown(event.payload);
     });
 
+    // Clicking a desktop notification opens the screen it is about
+    await listen('notification:navigate', async (event) => {
+        let view = event.payload;
+        if (view === 'scanning' && !(await invoke('is_scan_running'))) {
+            view = 'main';
+        }
+        if (view === 'region-auth') {
+            showRegionAuthView();
+        } else {
+            showView(view);
+        }
+    });
+
     // Listen for auth state changes from webviews
     await listen('platform-auth-changed', async (event) => {
         const { region, platform, authenticated } = event.payload;
@@ -1156,6 +1191,22 @@ function handleScanComplete(result) {
         `;
     }
 
+    const breakdown = document.getElementById('completeBreakdown');
+    if (breakdown) {
+        breakdown.innerHTML = (result?.by_platform || []).map(entry => {
+            const displayName = PLATFORM_NAMES[entry.platform] || capitalizeFirst(entry.platform || '');
+            const topCompetitor = entry.top_competitors?.[0];
+            return `
+                <div class="breakdown-row">
+                    <span class="breakdown-name">${escapeHtml(displayName)}</span>
+                    <span class="breakdown-counts">${entry.collected} ok · ${entry.failed} failed · ${entry.skipped} skipped</span>
+                    <span class="breakdown-rates">${Math.round(entry.mention_rate || 0)}% mentioned · ${Math.round(entry.citation_rate || 0)}% cited</span>
+                    ${topCompetitor ? `<span class="breakdown-competitor">Top competitor: ${escapeHtml(topCompetitor.name)} (${escapeHtml(topCompetitor.mentions)})</span>` : ''}
+                </div>
+            `;
+        }).join('');
+    }
+
     showView('complete');
     loadScheduleInfo();
     updateScanRunningIndicator(false);
@@ -1190,6 +1241,16 @@ function capitalizeFirst(str) {
     return str.charAt(0).toUpperCase() + str.slice(1);
 }
 
+// Escape text from the API or user configuration before putting it into innerHTML
+function escapeHtml(value) {
+    return String(value ?? '')
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+        .replace(/'/g, '&#39;');
+}
+
 // ==================== Initialize ====================
 document.addEventListener('DOMContentLoaded', () => {
     console.log('DOMContentLoaded fired');
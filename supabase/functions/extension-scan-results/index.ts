@@ -5,10 +5,20 @@ const corsHeaders = {
   'Access-Control-Allow-Headers': 'authorization, x-client-info, apikey, content-type',
 }
 
+interface TextSpan {
+  start: number  // UTF-16 index into responseText
+  end: number
+  text: string
+}
+
 interface CompetitorDetail {
   name: string
   position: number | null
   sentiment: 'positive' | 'neutral' | 'negative'
+  sentimentScore?: number | null
+  matchedAliases?: string[]
+  mentions?: number
+  spans?: TextSpan[]
 }
 
 interface ScanResult {
@@ -22,6 +32,7 @@ interface ScanResult {
   citationPresent: boolean
   position: number | null
   sentiment: 'positive' | 'neutral' | 'negative'
+  sentimentScore?: number | null
   competitorMentions: string[]
   competitorDetails?: CompetitorDetail[]
   citations: { url: string; title?: string; position?: number }[]
@@ -30,6 +41,8 @@ interface ScanResult {
   chat_url?: string  // Support both camelCase and snake_case
   requestCountry?: string  // Region code (e.g., 'us', 'uk', 'de') or 'local'
   request_country?: string  // Support both camelCase and snake_case
+  screenshotObjectPath?: string | null  // Object path of the answer image in the scan-snapshots bucket
+  resultKey?: string  // Session/country/platform/prompt/sample; a result sent again replaces its row
   metadata?: {
     modelUsed?: string
     hadWebSearch?: boolean
@@ -143,45 +156,92 @@ Deno.serve(async (req) => {
       )
     }
 
-    // Save raw result immediately (no AI evaluation - that's done async by worker)
-    // Use extension's basic analysis for now, worker will override with AI evaluation
+    // Save the result immediately with the client's local analysis (rank, sentiment, competitor
+    // details) as provisional values; the AI evaluation worker overwrites them asynchronously
     // Support both snake_case (from Rust) and camelCase field names
     const chatUrl = result.chat_url || result.chatUrl || null
     const creditsExhausted = result.creditsExhausted ?? false
     const requestCountry = result.request_country || result.requestCountry || null
 
-    // Save raw result - position/sentiment will be properly evaluated by AI worker
-    const { data: promptResult, error: insertError } = await supabaseAdmin
-      .from('prompt_results')
-      .insert({
-        prompt_id: result.promptId,
-        organization_id: organizationId,
-        product_id: result.productId,
-        scan_session_id: result.scanSessionId || null,
-        ai_model: result.platform,
-        response_text: result.responseText,
-        brand_mentioned: result.brandMentioned,  // Basic text search, AI will verify
-        citation_present: result.citationPresent,
-        position: null,  // Will be evaluated by AI worker
-        sentiment: 'neutral',  // Will be evaluated by AI worker
-        competitor_mentions: result.competitorMentions,  // Names only, AI will evaluate details
-        credits_exhausted: creditsExhausted,
-        chat_url: chatUrl,
-        request_country: requestCountry,
-        metadata: {
-          ...result.metadata,
-          aiEvaluated: false,  // Mark as not yet evaluated by AI
-          source: 'extension'
-        },
-        source: 'extension',
-        tested_at: new Date().toISOString()
-      })
-      .select()
-      .single()
+    const sentiment = SENTIMENTS.includes(result.sentiment) ? result.sentiment : 'neutral'
+
+    // Position/sentiment are provisional until evaluated by AI worker
+    const fields = {
+      prompt_id: result.promptId,
+      organization_id: organizationId,
+      product_id: result.productId,
+      scan_session_id: result.scanSessionId || null,
+      ai_model: result.platform,
+      response_text: result.responseText,
+      brand_mentioned: result.brandMentioned,  // Basic text search, AI will verify
+      citation_present: result.citationPresent,
+      position: result.position ?? null,  // Local rank, AI worker will re-evaluate
+      sentiment,  // Local lexicon sentiment, AI worker will re-evaluate
+      sentiment_score: result.sentimentScore ?? null,
+      competitor_mentions: result.competitorMentions,
+      credits_exhausted: creditsExhausted,
+      chat_url: chatUrl,
+      request_country: requestCountry,
+      screenshot_object_path: result.screenshotObjectPath ?? null,
+      metadata: {
+        ...result.metadata,
+        aiEvaluated: false,  // Mark as not yet evaluated by AI
+        source: 'extension'
+      },
+      source: 'extension'
+    }
+
+    // A result sent again (a retry after a lost response, or a re-analysis) replaces the
+    // row stored under its key, so it isn't counted twice
+    const resultKey = result.resultKey || null
+    let existingId: string | null = null
+    if (resultKey) {
+      const { data: existing, error: lookupError } = await supabaseAdmin
+        .from('prompt_results')
+        .select('id')
+        .eq('organization_id', organizationId)
+        .eq('result_key', resultKey)
+        .maybeSingle()
+
+      if (lookupError) {
+        throw new Error(`Failed to look up result: ${lookupError.message}`)
+      }
+      existingId = existing?.id ?? null
+    }
 
-    if (insertError) {
-      console.error('Error inserting prompt result:', insertError)
-      throw new Error(`Failed to save result: ${insertError.message}`)
+    const { data: promptResult, error: saveError } = existingId
+      ? await supabaseAdmin
+        .from('prompt_results')
+        .update(fields)
+        .eq('id', existingId)
+        .select()
+        .single()
+      : await supabaseAdmin
+        .from('prompt_results')
+        .insert({ ...fields, result_key: resultKey, tested_at: new Date().toISOString() })
+        .select()
+        .single()
+
+    if (saveError) {
+      console.error('Error saving prompt result:', saveError)
+      throw new Error(`Failed to save result: ${saveError.message}`)
+    }
+
+    // The replaced result's citations and mentions are rebuilt below
+    if (existingId) {
+      const { error: citationsDeleteError } = await supabaseAdmin
+        .from('prompt_citations')
+        .delete()
+        .eq('prompt_result_id', existingId)
+      const { error: mentionsDeleteError } = await supabaseAdmin
+        .from('competitor_mentions')
+        .delete()
+        .eq('prompt_result_id', existingId)
+
+      const deleteError = citationsDeleteError || mentionsDeleteError
+      if (deleteError) {
+        throw new Error(`Failed to replace result details: ${deleteError.message}`)
+      }
     }
 
     // Insert citations if present
@@ -203,9 +263,41 @@ Deno.serve(async (req) => {
         .insert(citationsToInsert)
     }
 
-    // Note: competitor_mentions are inserted by the AI evaluation worker
-    // which properly evaluates position and sentiment using GPT-4
-    // We don't insert them here to avoid duplicates
+    // Insert the locally analyzed mentions of tracked competitors as provisional rows;
+    // the AI evaluation worker replaces them with its own
+    if (result.competitorDetails && result.competitorDetails.length > 0) {
+      const { data: competitors } = await supabaseAdmin
+        .from('competitors')
+        .select('id, name')
+        .eq('product_id', result.productId)
+        .eq('status', 'tracking')
+
+      const competitorIds = new Map((competitors || []).map(c => [c.name.toLowerCase(), c.id]))
+      const mentionsToInsert = result.competitorDetails
+        .filter(detail => competitorIds.has(detail.name.toLowerCase()))
+        .map(detail => ({
+          organization_id: organizationId,
+          product_id: result.productId,
+          competitor_id: competitorIds.get(detail.name.toLowerCase()),
+          prompt_result_id: promptResult.id,
+          ai_model: result.platform,
+          position: detail.position ?? null,
+          mention_context: mentionContext(result.responseText, detail.spans?.[0]),
+          sentiment: SENTIMENTS.includes(detail.sentiment) ? detail.sentiment : 'neutral',
+          sentiment_score: detail.sentimentScore ?? null,
+          detected_at: new Date().toISOString()
+        }))
+
+      if (mentionsToInsert.length > 0) {
+        const { error: mentionsError } = await supabaseAdmin
+          .from('competitor_mentions')
+          .insert(mentionsToInsert)
+
+        if (mentionsError) {
+          console.error('Error inserting competitor mentions:', mentionsError)
+        }
+      }
+    }
 
     // Queue a job for AI evaluation (async processing by worker)
     const { error: jobError } = await supabaseAdmin
@@ -242,6 +334,7 @@ Deno.serve(async (req) => {
       JSON.stringify({
         success: true,
         resultId: promptResult.id,
+        updated: existingId !== null,
         message: 'Scan result saved, AI evaluation queued',
         queued: !jobError
       }),
@@ -257,6 +350,16 @@ Deno.serve(async (req) => {
 })
 
 // Helper functions
+const SENTIMENTS = ['positive', 'neutral', 'negative']
+
+// Text around a mention, like the worker's extractMentionContext
+function mentionContext(responseText: string, span?: TextSpan): string | null {
+  if (!span || !responseText) return null
+  const start = Math.max(0, span.start - 100)
+  const end = Math.min(responseText.length, span.end + 100)
+  return responseText.slice(start, end)
+}
+
 function extractDomain(url: string): string {
   try {
     const parsed = new URL(url)
@@ -0,0 +1,7 @@
+-- Sentiment score (-1 to 1) from the desktop app's local analysis, kept next to the
+-- provisional sentiment label until the AI evaluation worker re-evaluates the result
+ALTER TABLE prompt_results
+ADD COLUMN IF NOT EXISTS sentiment_score NUMERIC;
+
+ALTER TABLE competitor_mentions
+ADD COLUMN IF NOT EXISTS sentiment_score NUMERIC;
@@ -0,0 +1,7 @@
+-- Identity of a desktop app result (session, country, platform, prompt and sample), so a
+-- retried or re-analyzed result updates its row instead of adding a second one
+ALTER TABLE prompt_results
+ADD COLUMN IF NOT EXISTS result_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS prompt_results_organization_result_key_idx
+ON prompt_results (organization_id, result_key);
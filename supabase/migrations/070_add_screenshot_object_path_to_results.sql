@@ -0,0 +1,4 @@
+-- Storage object path (in the scan-snapshots bucket) of the desktop app's image of the answer.
+-- Best effort: the app only renders these on Windows
+ALTER TABLE prompt_results
+ADD COLUMN IF NOT EXISTS screenshot_object_path TEXT;
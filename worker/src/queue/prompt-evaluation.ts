@@ -131,6 +131,8 @@ export const promptEvaluationWorker = new Worker<PromptEvaluationJobData>(
           brand_mentioned: evaluation.brandMentioned,
           position: evaluation.position,
           sentiment: evaluation.sentiment,
+          // The local lexicon score belongs to the provisional label; the AI only gives a label
+          sentiment_score: null,
           competitor_mentions: evaluation.competitorMentions,
           metadata: {
             ...promptResult.metadata,
@@ -146,7 +148,17 @@ export const promptEvaluationWorker = new Worker<PromptEvaluationJobData>(
         throw new Error(`Failed to update prompt result: ${updateError.message}`)
       }
 
-      // 5. Insert competitor mentions if found (for tracked competitors)
+      // 5. Replace the client's provisional competitor mentions with the evaluated ones.
+      // If the provisional rows can't be removed, fail the job rather than count mentions twice
+      const { error: deleteError } = await supabase
+        .from('competitor_mentions')
+        .delete()
+        .eq('prompt_result_id', promptResultId)
+
+      if (deleteError) {
+        throw new Error(`Failed to remove provisional competitor mentions: ${deleteError.message}`)
+      }
+
       if (evaluation.competitorDetails.length > 0 && competitors) {
         // Create a map from competitor name to their details
         const detailsMap = new Map(
@@ -171,6 +183,7 @@ export const promptEvaluationWorker = new Worker<PromptEvaluationJobData>(
               position: details?.position || null,
               mention_context: mentionContext,
               sentiment: details?.sentiment || 'neutral',
+              sentiment_score: null,
               detected_at: new Date().toISOString()
             }
           })